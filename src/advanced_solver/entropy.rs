@@ -7,25 +7,54 @@ use crate::GameBoard;
 /// Represents the amount of possibilities that a board has based on the quantity of maybes that the
 /// board contains. This value is calculated based on the sum of the factorial of the quantity of
 /// maybes in a cell.
+///
+/// The calculation saturates at `u64::MAX` instead of overflowing. This can't happen for boards up
+/// to [MAX_SIZE](crate::MAX_SIZE), as even a 16x16 board with 16 maybes in every cell only has an
+/// entropy of `256 * 16!`, which is about `5.4 * 10^15`.
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq)]
 pub struct Entropy(u64);
 
 impl Entropy {
     /// Gets the approximate entropy of a board
-    pub fn entropy(board: &GameBoard) -> Self {
-        let mut entropy: u64 = 0;
-        for cell in board {
-            if let Some(maybe) = cell.maybe_values() {
-                entropy += factorial(maybe.len());
-            }
-        }
+    pub fn new(board: &GameBoard) -> Self {
+        let entropy = Self::per_cell(board)
+            .iter()
+            .flatten()
+            .fold(0u64, |entropy, &cell| entropy.saturating_add(cell));
         Self(entropy)
     }
+
+    /// Gets the approximate entropy of a board
+    #[deprecated(note = "use `Entropy::new` instead")]
+    #[allow(clippy::self_named_constructors)]
+    pub fn entropy(board: &GameBoard) -> Self {
+        Self::new(board)
+    }
+
+    /// The entropy as a number
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+
+    /// Gets each cell's contribution to the entropy of the board, indexed the same way as
+    /// [cells](GameBoard::cells). Cells without notes contribute nothing.
+    pub fn per_cell(board: &GameBoard) -> Vec<Vec<u64>> {
+        board
+            .cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell.maybe_values() {
+                        Some(maybe) => factorial(maybe.len()),
+                        None => 0,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
 }
 
+/// n!, saturating at `u64::MAX` for n > 20
 fn factorial(n: usize) -> u64 {
-    match n {
-        0 | 1 => 1,
-        n => n as u64 * factorial(n - 1),
-    }
+    (1..=n as u64).fold(1u64, |acc, i| acc.saturating_mul(i))
 }
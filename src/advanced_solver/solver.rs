@@ -1,11 +1,15 @@
 //! The algorithms that attempts to solve a sudoku board
 
 use crate::advanced_solver::techniques::*;
-use crate::GameBoard;
+use crate::{cell_index_to_rc, CellIndex, GameBoard};
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
 /// The difficulty of the sudoku board
-#[derive(Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Difficulty {
     /// Easy
     Easy = 0,
@@ -40,14 +44,100 @@ pub struct Solution {
     pub points: u64,
     /// The difficulty of the solve
     pub difficulty: Difficulty,
-    /// A list of moves made, listed as their (short, long) names
-    pub moves: Vec<(String, String)>,
+    /// The techniques applied to solve the board, in order
+    pub steps: Vec<SolveStep>,
 }
 
+impl Solution {
+    /// Describes each step of the solve on its own line, such as `"R3C5: Naked Single places 7"`.
+    /// Rows and columns are counted from 1.
+    pub fn narrate(&self) -> String {
+        self.steps
+            .iter()
+            .map(|step| match step.placed {
+                Some((cell, value)) => format!(
+                    "{}: {} places {}",
+                    cell_index_to_rc(cell),
+                    step.long_name,
+                    value
+                ),
+                None => format!("{} removes candidates", step.long_name),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// How far the known techniques got on a board they couldn't solve
+pub struct PartialSolution {
+    /// The board after applying every technique that made progress
+    pub board: GameBoard,
+    /// The techniques applied to the board, in order
+    pub steps: Vec<SolveStep>,
+}
+
+/// A technique applied to a board while solving it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveStep {
+    /// The short name of the technique
+    pub short_name: String,
+    /// The long name of the technique
+    pub long_name: String,
+    /// The points the technique is worth
+    pub points: u64,
+    /// The cell the technique placed a value in, and that value. `None` if the technique only
+    /// removed notes
+    pub placed: Option<(CellIndex, u8)>,
+}
+
+impl SolveStep {
+    /// The step of applying a technique, which changed the board from `before` to `after`
+    fn new(technique: &dyn Technique, before: &GameBoard, after: &GameBoard) -> Self {
+        let placed = before.iter_unset().into_iter().find_map(|cell| {
+            after.cell_value(cell).as_value().map(|value| (cell, value))
+        });
+        SolveStep {
+            short_name: technique.short_name(),
+            long_name: technique.long_name(),
+            points: technique.points(),
+            placed,
+        }
+    }
+
+    /// The difficulty of the board if this was the hardest step
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::from(self.points)
+    }
+}
+
+/// The next value that can be placed on a board, and how it was found
+pub struct Hint {
+    /// The cell the value can be placed in
+    pub cell: CellIndex,
+    /// The value of the cell
+    pub value: u8,
+    /// The long names of the techniques used to find the value
+    pub techniques: Vec<String>,
+}
+
+/// The points added to a solution when the rest of the board had to be brute forced
+pub const GUESS_POINTS: u64 = 2000;
+
 /// A sudoku solver
 pub struct Solver {
     techniques: Vec<Box<dyn Technique>>,
+    /// The short names of techniques that aren't used
+    disabled: HashSet<String>,
     timeout_duration: Duration,
+    /// Shuffles the order techniques are tried in when solving
+    order_seed: Option<u64>,
+}
+
+impl Default for Solver {
+    /// A solver that never times out
+    fn default() -> Self {
+        Solver::new(Duration::MAX)
+    }
 }
 
 macro_rules! techniques {
@@ -60,40 +150,147 @@ impl Solver {
     /// Creates a new instance of the solver, that can timeout
     pub fn new(timeout: Duration) -> Self {
         let mut techniques: Vec<Box<dyn Technique>> =
-            techniques![NakedSingle, HiddenSingle, NakedPair, HiddenPair];
+            techniques![
+            NakedSingle,
+            HiddenSingle,
+            NakedPair,
+            HiddenPair,
+            BoxLineReduction,
+            HiddenTriple,
+            Bug
+        ];
 
         techniques.sort_by_key(|technique| technique.points());
 
         Solver {
             techniques,
+            disabled: HashSet::new(),
             timeout_duration: timeout,
+            order_seed: None,
         }
     }
 
-    /// Attempts to solve the board using known techniques. Returns either the solution, or an
-    /// incomplete board that the known techniques were able to achieve.
+    /// Tries the techniques in a shuffled order when solving, instead of from least to most points.
+    /// The same seed always gives the same order. The solved board shouldn't depend on the order,
+    /// but the points can.
+    pub fn with_order_seed(mut self, seed: u64) -> Self {
+        self.order_seed = Some(seed);
+        self
+    }
+
+    /// Stops the solver from using the technique with the given short name
+    pub fn disable(&mut self, short_name: &str) {
+        self.disabled.insert(short_name.to_string());
+    }
+
+    /// Lets the solver use the technique with the given short name again
+    pub fn enable(&mut self, short_name: &str) {
+        self.disabled.remove(short_name);
+    }
+
+    /// Whether the solver uses the technique with the given short name
+    pub fn is_enabled(&self, short_name: &str) -> bool {
+        !self.disabled.contains(short_name)
+    }
+
+    /// The techniques that aren't disabled, from least to most points
+    fn enabled_techniques(&self) -> impl Iterator<Item = &dyn Technique> {
+        self.techniques
+            .iter()
+            .map(|technique| technique.as_ref())
+            .filter(move |technique| self.is_enabled(&technique.short_name()))
+    }
+
+    /// Attempts to solve the board using known techniques. Returns either the solution, or the
+    /// incomplete board that the known techniques were able to achieve. Both list the steps taken.
     ///
     /// Will not brute force.
-    pub fn solve(&self, board: &GameBoard) -> Result<Solution, GameBoard> {
+    pub fn solve(&self, board: &GameBoard) -> Result<Solution, PartialSolution> {
+        let (board, points, steps) = self.apply_techniques(board);
+
+        if board.is_victory() {
+            Ok(Solution {
+                solved_board: board,
+                points,
+                difficulty: Difficulty::from(points),
+                steps,
+            })
+        } else {
+            Err(PartialSolution { board, steps })
+        }
+    }
+
+    /// Attempts to solve the board using known techniques, and brute forces the rest of the board
+    /// once the techniques can't make any more progress. Each cell filled in by brute force is
+    /// listed as a `"guess"` step, and guessing adds `GUESS_POINTS` to the points of the solution,
+    /// which are given to the first guess.
+    ///
+    /// Returns the board the known techniques were able to achieve if it can't be solved.
+    pub fn solve_with_guessing(&self, board: &GameBoard) -> Result<Solution, PartialSolution> {
+        let (board, mut points, mut steps) = self.apply_techniques(board);
+        if board.is_victory() {
+            return Ok(Solution {
+                solved_board: board,
+                points,
+                difficulty: Difficulty::from(points),
+                steps,
+            });
+        }
+
+        // brute forcing isn't limited by time or the number of solutions, as the board is only
+        // known to be unsolvable once every guess has been tried
+        let mut solved_board = board.clone();
+        if !solved_board.solve_iterative() || !solved_board.is_victory() {
+            return Err(PartialSolution { board, steps });
+        }
+
+        points += GUESS_POINTS;
+        // only the first guess is worth points, so the points of the steps add up to the total
+        for (i, cell) in board.iter_unset().into_iter().enumerate() {
+            steps.push(SolveStep {
+                short_name: "guess".to_string(),
+                long_name: "Guess".to_string(),
+                points: if i == 0 { GUESS_POINTS } else { 0 },
+                placed: solved_board.cell_value(cell).as_value().map(|value| (cell, value)),
+            });
+        }
+
+        Ok(Solution {
+            solved_board,
+            points,
+            difficulty: Difficulty::from(points),
+            steps,
+        })
+    }
+
+    /// Applies known techniques to the board until none of them make any more progress, returning
+    /// the new board, the points earned, and the steps taken
+    fn apply_techniques(&self, board: &GameBoard) -> (GameBoard, u64, Vec<SolveStep>) {
         let mut board = board.clone(); // create solvers own sandbox for the board
+        board.set_auto_remove(true); // techniques rely on notes being kept up to date
         board.clear_notes(); // clear all notes in the board
-        board.auto_note(); // creates own notes that are only maybes
+        let _ = board.auto_note(); // creates own notes that are only maybes
         let mut points: u64 = 0;
-        let mut moves = vec![];
+        let mut steps = vec![];
+
+        let mut techniques: Vec<&dyn Technique> = self.enabled_techniques().collect();
+        if let Some(seed) = self.order_seed {
+            techniques.shuffle(&mut Pcg64::seed_from_u64(seed));
+        }
 
         let start = Instant::now();
 
         let mut cont = true;
         while cont {
             cont = false;
-            for technique in &self.techniques {
+            for &technique in &techniques {
                 if start.elapsed() >= self.timeout_duration {
                     break;
                 }
 
                 if let Ok(new_board) = technique.apply_to(&board) {
                     points += technique.points();
-                    moves.push((technique.short_name(), technique.long_name()));
+                    steps.push(SolveStep::new(technique, &board, &new_board));
 
                     board = new_board;
                     cont = true;
@@ -102,15 +299,79 @@ impl Solver {
             }
         }
 
-        if board.is_victory() {
-            Ok(Solution {
-                solved_board: board,
-                points,
-                difficulty: Difficulty::from(points),
-                moves,
-            })
-        } else {
-            Err(board)
+        (board, points, steps)
+    }
+
+    /// Finds the next value that can be placed on the board using known techniques, without
+    /// changing the board. Returns `None` if the known techniques can't place any value.
+    pub fn hint(&self, board: &GameBoard) -> Option<Hint> {
+        let mut working = board.clone();
+        working.set_auto_remove(true);
+        working.clear_notes();
+        let _ = working.auto_note();
+        let mut techniques = vec![];
+
+        let start = Instant::now();
+
+        while start.elapsed() < self.timeout_duration {
+            let (technique, new_board) = self.enabled_techniques().find_map(|technique| {
+                technique
+                    .apply_to(&working)
+                    .ok()
+                    .map(|new_board| (technique, new_board))
+            })?;
+            techniques.push(technique.long_name());
+            working = new_board;
+
+            for cell in board.iter_unset() {
+                if let Some(value) = working.cell_value(cell).as_value() {
+                    return Some(Hint {
+                        cell,
+                        value,
+                        techniques,
+                    });
+                }
+            }
         }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_creator::{GameCreator, LineLoader};
+
+    /// The points of each step, added together
+    fn step_points(solution: &Solution) -> u64 {
+        solution.steps.iter().map(|step| step.points).sum()
+    }
+
+    #[test]
+    fn guessing_solves_boards_with_many_solutions() {
+        let board = GameBoard::new();
+        let solution = Solver::default()
+            .solve_with_guessing(&board)
+            .unwrap_or_else(|_| panic!("An empty board can be solved by guessing"));
+        assert!(solution.solved_board.is_victory());
+        assert_eq!(solution.points, step_points(&solution));
+    }
+
+    #[test]
+    fn guess_steps_add_up_to_the_points() {
+        // a solved board with a rectangle of four cells removed, which can be filled two ways
+        let board = LineLoader::from_string(
+            "003456789456789123789123456004365897365897214897214365531642978642978531978531642",
+        )
+        .into_game()
+        .unwrap();
+        let solution = Solver::default()
+            .solve_with_guessing(&board)
+            .unwrap_or_else(|_| panic!("The board can be solved by guessing"));
+        assert!(board.is_solved_by(&solution.solved_board));
+        assert_eq!(solution.points, step_points(&solution));
+        assert!(solution.points >= GUESS_POINTS);
+        assert!(solution.steps.iter().any(|step| step.short_name == "guess"));
     }
 }
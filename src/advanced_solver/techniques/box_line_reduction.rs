@@ -0,0 +1,86 @@
+//! The box/line reduction technique
+
+use crate::advanced_solver::techniques::Technique;
+use crate::validity::SudokuCorrectness;
+use crate::{AffectedComponents, CellIndex, GameBoard};
+
+/// Detects when every cell of a row or column that can be a value is in the same house. The value
+/// must be in that row or column, so no other cell in the house can be that value. Also known as
+/// claiming
+pub struct BoxLineReduction;
+
+impl BoxLineReduction {
+    /// Tries to reduce the maybes of the houses that intersect with a row or column.
+    ///
+    /// Returns the new board if any maybes were removed
+    pub fn reduce<S: SudokuCorrectness>(&self, line: &S, board: &GameBoard) -> Option<GameBoard> {
+        let house_of = |(column, row): CellIndex| {
+            (row / board.house_height(), column / board.house_width())
+        };
+
+        for val in 1..=board.size() as u8 {
+            let cells: Vec<CellIndex> = line
+                .indices_and_cells()
+                .into_iter()
+                .filter(|(_, cell)| cell.as_value().is_none() && cell.is_or_maybe(val))
+                .map(|(index, _)| index)
+                .collect();
+
+            let house = match cells.first() {
+                Some(&index) => house_of(index),
+                None => continue,
+            };
+            if cells.iter().any(|&index| house_of(index) != house) {
+                continue;
+            }
+
+            let mut next_board = board.clone();
+            let mut changed = false;
+            for (index, cell) in AffectedComponents::new(board, cells[0])
+                .house()
+                .indices_and_cells()
+            {
+                if !cells.contains(&index) && cell.as_value().is_none() && cell.is_or_maybe(val) {
+                    next_board.remove_maybe(index, val);
+                    changed = true;
+                }
+            }
+
+            if changed {
+                return Some(next_board);
+            }
+        }
+
+        None
+    }
+}
+
+impl Technique for BoxLineReduction {
+    fn points(&self) -> u64 {
+        70
+    }
+
+    fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
+        for row in game_board.rows() {
+            if let Some(ret) = self.reduce(&row, game_board) {
+                return Ok(ret);
+            }
+        }
+
+        for col in game_board.columns() {
+            if let Some(ret) = self.reduce(&col, game_board) {
+                return Ok(ret);
+            }
+        }
+
+        Err(())
+    }
+
+    fn long_name(&self) -> String {
+        "Box/Line Reduction".to_string()
+    }
+
+    fn short_name(&self) -> String {
+        "bxln".to_string()
+    }
+}
@@ -0,0 +1,78 @@
+//! The bivalue universal grave (BUG+1) technique
+
+use crate::advanced_solver::techniques::Technique;
+use crate::game_board_controller::NoteMode;
+use crate::validity::SudokuCorrectness;
+use crate::{AffectedComponents, CellIndex, GameBoard};
+
+/// Detects a bivalue universal grave plus one, where every unset cell has two maybes except for
+/// one cell with three. If that cell wasn't the value that appears an odd number of times in its
+/// units, the board would have more than one solution, so it must be that value
+pub struct Bug;
+
+impl Bug {
+    /// Finds the only unset cell with three maybes, if every other unset cell has exactly two
+    pub fn find_trivalue_cell(&self, board: &GameBoard) -> Option<CellIndex> {
+        let mut trivalue = None;
+        for cell_index in board.iter_unset() {
+            let maybes = board[cell_index].maybe_values()?;
+            match maybes.len() {
+                2 => {}
+                3 if trivalue.is_none() => trivalue = Some(cell_index),
+                _ => return None,
+            }
+        }
+
+        trivalue
+    }
+
+    /// Whether a value is a maybe of an odd number of cells in a component
+    fn appears_odd<S: SudokuCorrectness>(comp: &S, val: u8) -> bool {
+        let count = comp
+            .indices_and_cells()
+            .into_iter()
+            .filter(|(_, cell)| cell.as_value().is_none() && cell.is_or_maybe(val))
+            .count();
+        count % 2 == 1
+    }
+}
+
+impl Technique for Bug {
+    fn points(&self) -> u64 {
+        500
+    }
+
+    fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
+        let cell_index = self.find_trivalue_cell(game_board).ok_or(())?;
+        let affected = AffectedComponents::new(game_board, cell_index);
+        let (row, column, house) = (affected.row(), affected.column(), affected.house());
+
+        let odd: Vec<u8> = game_board[cell_index]
+            .maybe_values()
+            .unwrap()
+            .into_iter()
+            .filter(|&val| {
+                Self::appears_odd(&row, val)
+                    && Self::appears_odd(&column, val)
+                    && Self::appears_odd(&house, val)
+            })
+            .collect();
+
+        match odd.as_slice() {
+            &[val] => {
+                let mut next = game_board.clone();
+                next.set(cell_index, &NoteMode::Value, val);
+                Ok(next)
+            }
+            _ => Err(()),
+        }
+    }
+
+    fn long_name(&self) -> String {
+        "Bivalue Universal Grave".to_string()
+    }
+
+    fn short_name(&self) -> String {
+        "bug1".to_string()
+    }
+}
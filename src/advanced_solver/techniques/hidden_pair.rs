@@ -1,9 +1,11 @@
 //! The hidden pair technique
 
+use crate::advanced_solver::techniques::subsets::apply_hidden_subset;
 use crate::advanced_solver::techniques::Technique;
 use crate::GameBoard;
 
-/// Detects a hidden pair
+/// Detects a hidden pair, where two values can only be in the same two cells of a row, column,
+/// or house. Those cells can't be any other value
 pub struct HiddenPair;
 
 impl Technique for HiddenPair {
@@ -12,11 +14,7 @@ impl Technique for HiddenPair {
     }
 
     fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
-
-
-
-
-        Err(())
+        apply_hidden_subset(game_board, 2).ok_or(())
     }
 
     fn long_name(&self) -> String {
@@ -26,4 +24,4 @@ impl Technique for HiddenPair {
     fn short_name(&self) -> String {
         "hdpr".to_string()
     }
-}
\ No newline at end of file
+}
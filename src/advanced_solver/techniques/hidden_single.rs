@@ -2,27 +2,29 @@
 
 use crate::advanced_solver::techniques::Technique;
 use crate::game_board_controller::NoteMode;
-use crate::{AffectedComponents, GameBoard};
+use crate::{AffectedComponents, CellIndex, GameBoard};
 
 /// Detects a hidden single, where a cell isn't alone in a cell, but it's the only cell that can be
 /// that value in it's house, row, or column
 pub struct HiddenSingle;
 
-impl Technique for HiddenSingle {
-    fn points(&self) -> u64 {
-        10
-    }
-
-    fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
+impl HiddenSingle {
+    /// Finds a cell that's the only cell in its row, column, or house that can be one of its
+    /// maybes, and that maybe
+    pub fn find_single(&self, game_board: &GameBoard) -> Option<(CellIndex, u8)> {
         for cell_index in game_board.iter_unset() {
             let cell = game_board[cell_index];
+            // empty cells, such as ones left without candidates, have no maybes to look at
+            let maybes = match cell.maybe_values() {
+                Some(maybes) => maybes,
+                None => continue,
+            };
 
             let affected = AffectedComponents::new(game_board, cell_index);
             let row = affected.row();
             let column = affected.column();
             let house = affected.house();
 
-            let maybes = cell.maybe_values().unwrap();
             for maybe in maybes {
                 if row
                     .iter()
@@ -30,9 +32,7 @@ impl Technique for HiddenSingle {
                     .sum::<usize>()
                     == 1
                 {
-                    let mut next = game_board.clone();
-                    next.set(cell_index, &NoteMode::Value, maybe);
-                    return Ok(next);
+                    return Some((cell_index, maybe));
                 }
 
                 if column
@@ -41,9 +41,7 @@ impl Technique for HiddenSingle {
                     .sum::<usize>()
                     == 1
                 {
-                    let mut next = game_board.clone();
-                    next.set(cell_index, &NoteMode::Value, maybe);
-                    return Ok(next);
+                    return Some((cell_index, maybe));
                 }
 
                 if house
@@ -53,28 +51,29 @@ impl Technique for HiddenSingle {
                     .sum::<usize>()
                     == 1
                 {
-                    let mut next = game_board.clone();
-                    next.set(cell_index, &NoteMode::Value, maybe);
-                    return Ok(next);
+                    return Some((cell_index, maybe));
                 }
-                /*
-
-
-
+            }
+        }
 
-                for row in house.iter() {
-                    if row.iter().map(|cell| if cell.is_or_maybe(maybe) { 1 } else { 0 })
-                        .sum::<usize>() == 1
-                    {
+        None
+    }
+}
 
-                    }
-                }
+impl Technique for HiddenSingle {
+    fn points(&self) -> u64 {
+        10
+    }
 
-                  */
-            }
-        }
+    fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
+        let (cell_index, val) = self.find_single(game_board).ok_or(())?;
+        let mut next = game_board.clone();
+        next.set(cell_index, &NoteMode::Value, val);
+        Ok(next)
+    }
 
-        Err(())
+    fn is_applicable(&self, game_board: &GameBoard) -> bool {
+        self.find_single(game_board).is_some()
     }
 
     fn long_name(&self) -> String {
@@ -85,3 +84,36 @@ impl Technique for HiddenSingle {
         "hdns".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::advanced_solver::Solver;
+    use crate::CellValue;
+
+    /// A board where (8, 0) has no candidates, as its row has 1 through 8 and its column has 9
+    fn board_with_dead_cell() -> GameBoard {
+        let mut board = GameBoard::new();
+        for column in 0..8 {
+            board[(column, 0)] = CellValue::Preset(column as u8 + 1);
+        }
+        board[(8, 5)] = CellValue::Preset(9);
+        board
+    }
+
+    #[test]
+    fn skips_cells_without_notes() {
+        let mut board = board_with_dead_cell();
+        assert_eq!(board.auto_note(), Err((8, 0)));
+        assert_eq!(board.cell_value((8, 0)), &CellValue::Empty);
+
+        assert_eq!(HiddenSingle.find_single(&board), None);
+        assert!(HiddenSingle.apply_to(&board).is_err());
+    }
+
+    #[test]
+    fn hint_on_board_with_dead_cell() {
+        assert!(Solver::default().hint(&board_with_dead_cell()).is_none());
+        assert!(Solver::default().solve(&board_with_dead_cell()).is_err());
+    }
+}
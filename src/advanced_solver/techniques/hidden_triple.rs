@@ -0,0 +1,79 @@
+//! The hidden triple technique
+
+use crate::advanced_solver::techniques::subsets::apply_hidden_subset;
+use crate::advanced_solver::techniques::Technique;
+use crate::GameBoard;
+
+/// Detects a hidden triple, where three values can only be in the same three cells of a row,
+/// column, or house. Those cells can't be any other value
+pub struct HiddenTriple;
+
+impl Technique for HiddenTriple {
+    fn points(&self) -> u64 {
+        200
+    }
+
+    fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
+        apply_hidden_subset(game_board, 3).ok_or(())
+    }
+
+    fn long_name(&self) -> String {
+        "Hidden Triple".to_string()
+    }
+
+    fn short_name(&self) -> String {
+        "hdtr".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CellValue;
+
+    fn notes(values: &[u8]) -> CellValue {
+        let maybe = values.iter().fold(0, |maybe, &val| maybe | 1 << (val - 1));
+        CellValue::Notes { maybe, deny: 0 }
+    }
+
+    /// A board where 1, 2, and 3 can only be in (0, 0), (1, 0), and (4, 0) of the first row, and
+    /// every cell outside of the first row can be anything
+    fn board_with_hidden_triple() -> GameBoard {
+        let mut board = GameBoard::new();
+        for row in 1..9 {
+            for column in 0..9 {
+                board[(column, row)] = notes(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+            }
+        }
+        for column in 0..9 {
+            board[(column, 0)] = notes(&[4, 5, 6, 7, 8, 9]);
+        }
+        board[(0, 0)] = notes(&[1, 2, 4, 5]);
+        board[(1, 0)] = notes(&[2, 3, 6, 7]);
+        board[(4, 0)] = notes(&[1, 3, 8, 9]);
+        board
+    }
+
+    #[test]
+    fn removes_other_values_from_the_triple() {
+        let board = board_with_hidden_triple();
+        let next = HiddenTriple.apply_to(&board).unwrap();
+
+        assert_eq!(next.cell_value((0, 0)).maybe_values(), Some(vec![1, 2]));
+        assert_eq!(next.cell_value((1, 0)).maybe_values(), Some(vec![2, 3]));
+        assert_eq!(next.cell_value((4, 0)).maybe_values(), Some(vec![1, 3]));
+        for row in 0..9 {
+            for column in 0..9 {
+                if ![(0, 0), (1, 0), (4, 0)].contains(&(column, row)) {
+                    assert_eq!(next[(column, row)], board[(column, row)]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn does_nothing_once_the_triple_is_enforced() {
+        let next = HiddenTriple.apply_to(&board_with_hidden_triple()).unwrap();
+        assert!(HiddenTriple.apply_to(&next).is_err());
+    }
+}
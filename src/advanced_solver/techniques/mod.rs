@@ -18,8 +18,29 @@ pub trait Technique {
     ///
     /// If the technique was successfully applied, the new board is returned as `Ok(board)`. Otherwise,
     /// `Err(())` is returned.
+    #[allow(clippy::result_unit_err)]
     fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()>;
 
+    /// Whether the technique can be applied to the game board. By default this applies the
+    /// technique and throws away the new board, so techniques should override it if they can check
+    /// more cheaply.
+    fn is_applicable(&self, game_board: &GameBoard) -> bool {
+        self.apply_to(game_board).is_ok()
+    }
+
+    /// Apply the technique to the game board until it can't be applied anymore, returning the
+    /// final board.
+    fn apply_all(&self, game_board: &GameBoard) -> GameBoard {
+        let mut board = game_board.clone();
+        while let Ok(next) = self.apply_to(&board) {
+            if next == board {
+                break;
+            }
+            board = next;
+        }
+        board
+    }
+
     /// Gets the long form of the name of the technique
     fn long_name(&self) -> String;
 
@@ -27,6 +48,7 @@ pub trait Technique {
     fn short_name(&self) -> String;
 }
 
+pub mod subsets;
 
 mod naked_single;
 pub use naked_single::NakedSingle;
@@ -39,3 +61,12 @@ pub use naked_pair::NakedPair;
 
 mod hidden_pair;
 pub use hidden_pair::HiddenPair;
+
+mod hidden_triple;
+pub use hidden_triple::HiddenTriple;
+
+mod box_line_reduction;
+pub use box_line_reduction::BoxLineReduction;
+
+mod bug;
+pub use bug::Bug;
@@ -1,46 +1,19 @@
 //! The naked pair technique
 
-use std::collections::HashMap;
-
+use crate::advanced_solver::techniques::subsets::{apply_naked_subset, naked_subset};
 use crate::advanced_solver::techniques::Technique;
-use crate::game_board_controller::NoteMode;
 use crate::validity::SudokuCorrectness;
-use crate::{AffectedComponents, CellIndex, CellValue, GameBoard};
+use crate::{AffectedComponents, CellIndex, GameBoard};
 
 /// Detects a naked pair
 pub struct NakedPair;
 
 impl NakedPair {
-    /// Tries to find a naked pair.
+    /// Tries to find a naked pair in a component.
     ///
     /// Returns a pair of cell indexes if this is successful
-    pub fn find_pair<'a, I>(&self, iter: I) -> Option<(CellIndex, CellIndex)>
-    where
-        I: IntoIterator<Item = (CellIndex, &'a CellValue)>,
-    {
-        //println!("Attempting to find naked pairs...");
-        let mut pair_mapping: HashMap<_, Vec<_>> = HashMap::new();
-        for (cell_index, value) in iter {
-            if let Some(maybes) = value.maybe_values() {
-                //println!("Maybes: {:?}", maybes);
-                match maybes.as_slice() {
-                    &[v1, v2] => {
-                        pair_mapping.entry((v1, v2)).or_default().push(cell_index);
-                    }
-                    _ => {}
-                }
-            }
-        }
-
-
-        for (_key, indices) in pair_mapping {
-            if let &[index1, index2] = indices.as_slice() {
-                //println!("found naked pair: {:?}", (index1, index2));
-                return Some((index1, index2));
-            }
-        }
-
-        None
+    pub fn find_pair<S: SudokuCorrectness>(&self, comp: &S) -> Option<(CellIndex, CellIndex)> {
+        naked_subset(comp, 2).map(|(cells, _)| (cells[0], cells[1]))
     }
 
     /// Enforces a pair in a row
@@ -49,7 +22,7 @@ impl NakedPair {
         pair: (CellIndex, CellIndex),
         board: &GameBoard,
     ) -> Option<GameBoard> {
-        let affected_row = AffectedComponents::new(&board, pair.0).row();
+        let affected_row = AffectedComponents::new(board, pair.0).row();
 
         self.enforce(pair, board, affected_row)
     }
@@ -60,7 +33,7 @@ impl NakedPair {
         pair: (CellIndex, CellIndex),
         board: &GameBoard,
     ) -> Option<GameBoard> {
-        let affected_col = AffectedComponents::new(&board, pair.0).column();
+        let affected_col = AffectedComponents::new(board, pair.0).column();
 
         self.enforce(pair, board, affected_col)
     }
@@ -71,7 +44,7 @@ impl NakedPair {
         pair: (CellIndex, CellIndex),
         board: &GameBoard,
     ) -> Option<GameBoard> {
-        let affected_house = AffectedComponents::new(&board, pair.0).house();
+        let affected_house = AffectedComponents::new(board, pair.0).house();
 
         self.enforce(pair, board, affected_house)
     }
@@ -93,12 +66,12 @@ impl NakedPair {
         {
             if let Some(maybes) = cell.maybe_values() {
                 if maybes.contains(&values[0]) {
-                    next_board.set(index, &NoteMode::Deny, values[0]);
+                    next_board.remove_maybe(index, values[0]);
                     changed = true;
                 }
 
                 if maybes.contains(&values[1]) {
-                    next_board.set(index, &NoteMode::Deny, values[1]);
+                    next_board.remove_maybe(index, values[1]);
                     changed = true;
                 }
             }
@@ -118,38 +91,76 @@ impl Technique for NakedPair {
     }
 
     fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
-        for row in game_board.rows() {
-            if let Some(pair) = self.find_pair(row.indices_and_cells()) {
-                if let Some(ret) = self.enforce_row_pair(pair, game_board) {
-                    return Ok(ret);
-                }
-            }
-        }
+        apply_naked_subset(game_board, 2).ok_or(())
+    }
 
-        for col in game_board.columns() {
-            if let Some(pair) = self.find_pair(col.indices_and_cells()) {
-                if let Some(ret) = self.enforce_column_pair(pair, game_board) {
-                    return Ok(ret);
-                }
-            }
+    fn long_name(&self) -> String {
+        "Naked Pair".to_string()
+    }
+
+    fn short_name(&self) -> String {
+        "nkpr".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CellValue;
+
+    fn notes(values: &[u8], denied: &[u8]) -> CellValue {
+        let mask = |values: &[u8]| values.iter().fold(0, |mask, &val| mask | 1 << (val - 1));
+        CellValue::Notes {
+            maybe: mask(values),
+            deny: mask(denied),
         }
+    }
 
-        for house in game_board.houses() {
-            if let Some(pair) = self.find_pair(house.indices_and_cells()) {
-                if let Some(ret) = self.enforce_house_pair(pair, game_board) {
-                    return Ok(ret);
-                }
+    /// A board where (0, 0) and (1, 0) are a naked pair of 1 and 2, and (5, 0) has 3 denied
+    fn board_with_naked_pair() -> GameBoard {
+        let mut board = GameBoard::new();
+        for row in 0..9 {
+            for column in 0..9 {
+                board[(column, row)] = notes(&[1, 2, 3, 4, 5, 6, 7, 8, 9], &[]);
             }
         }
-
-        Err(())
+        board[(0, 0)] = notes(&[1, 2], &[]);
+        board[(1, 0)] = notes(&[1, 2], &[]);
+        board[(5, 0)] = notes(&[1, 2, 4, 5, 6, 7, 8, 9], &[3]);
+        board
     }
 
-    fn long_name(&self) -> String {
-        "Naked Pair".to_string()
+    #[test]
+    fn enforcing_a_pair_twice_changes_nothing() {
+        let board = board_with_naked_pair();
+        let pair = ((0, 0), (1, 0));
+        let next = NakedPair.enforce_row_pair(pair, &board).unwrap();
+
+        for column in 2..9 {
+            let maybes = next.cell_value((column, 0)).maybe_values().unwrap();
+            assert!(!maybes.contains(&1) && !maybes.contains(&2));
+        }
+        assert_eq!(next[(5, 0)], notes(&[4, 5, 6, 7, 8, 9], &[3]));
+        assert!(NakedPair.enforce_row_pair(pair, &next).is_none());
     }
 
-    fn short_name(&self) -> String {
-        "nkpr".to_string()
+    #[test]
+    fn eliminations_stick_across_applications() {
+        let mut board = board_with_naked_pair();
+        while let Ok(next) = NakedPair.apply_to(&board) {
+            for row in 0..9 {
+                for column in 0..9 {
+                    let before = board.cell_value((column, row)).maybe_values().unwrap();
+                    let after = next.cell_value((column, row)).maybe_values().unwrap();
+                    assert!(after.iter().all(|val| before.contains(val)));
+                }
+            }
+            assert_ne!(next, board);
+            board = next;
+        }
+
+        assert_eq!(board[(5, 0)], notes(&[4, 5, 6, 7, 8, 9], &[3]));
+        assert_eq!(board[(0, 0)], notes(&[1, 2], &[]));
+        assert_eq!(board[(1, 0)], notes(&[1, 2], &[]));
     }
 }
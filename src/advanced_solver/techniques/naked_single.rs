@@ -2,30 +2,40 @@
 
 use crate::advanced_solver::techniques::Technique;
 use crate::game_board_controller::NoteMode;
-use crate::{CellValue, GameBoard};
+use crate::{CellIndex, CellValue, GameBoard};
 
 /// Detects a naked single
 pub struct NakedSingle;
 
+impl NakedSingle {
+    /// Finds a cell with only one maybe, and that maybe
+    pub fn find_single(&self, game_board: &GameBoard) -> Option<(CellIndex, u8)> {
+        game_board.iter_unset().into_iter().find_map(|cell_index| {
+            let cell = game_board.cell_value(cell_index);
+            if let CellValue::Notes { .. } = cell {
+                if let &[val] = cell.maybe_values().unwrap().as_slice() {
+                    return Some((cell_index, val));
+                }
+            }
+            None
+        })
+    }
+}
+
 impl Technique for NakedSingle {
     fn points(&self) -> u64 {
         5
     }
 
     fn apply_to(&self, game_board: &GameBoard) -> Result<GameBoard, ()> {
-        for cell_index in game_board.iter_unset() {
-            let cell = game_board.cell_value(cell_index);
-            if let CellValue::Notes { .. } = cell {
-                let maybe = cell.maybe_values().unwrap();
-                if let &[val] = maybe.as_slice() {
-                    let mut next = game_board.clone();
-                    next.set(cell_index, &NoteMode::Value, val);
-                    return Ok(next);
-                }
-            }
-        }
+        let (cell_index, val) = self.find_single(game_board).ok_or(())?;
+        let mut next = game_board.clone();
+        next.set(cell_index, &NoteMode::Value, val);
+        Ok(next)
+    }
 
-        Err(())
+    fn is_applicable(&self, game_board: &GameBoard) -> bool {
+        self.find_single(game_board).is_some()
     }
 
     fn long_name(&self) -> String {
@@ -0,0 +1,224 @@
+//! Helpers for finding naked and hidden subsets in a row, column, or house.
+//!
+//! A naked subset is `n` cells that can only be `n` values between them, so no other cell in the
+//! component can be one of those values. A hidden subset is `n` values that can only be in `n`
+//! cells, so those cells can't be any other value.
+
+use crate::validity::SudokuCorrectness;
+use crate::{CellIndex, GameBoard};
+
+/// A subset of cells in a component, and the values they're limited to
+pub type Subset = (Vec<CellIndex>, Vec<u8>);
+
+/// Finds every naked subset of `n` cells in a component. Only cells with between 2 and `n`
+/// maybes are part of naked subsets
+pub fn naked_subsets<S: SudokuCorrectness>(comp: &S, n: usize) -> Vec<Subset> {
+    let cells: Vec<(CellIndex, Vec<u8>)> = comp
+        .indices_and_cells()
+        .into_iter()
+        .filter_map(|(index, cell)| cell.maybe_values().map(|maybes| (index, maybes)))
+        .filter(|(_, maybes)| maybes.len() >= 2 && maybes.len() <= n)
+        .collect();
+
+    let mut subsets = vec![];
+    for combination in combinations(cells.len(), n) {
+        let mut values: Vec<u8> = vec![];
+        for &i in &combination {
+            for &val in &cells[i].1 {
+                if !values.contains(&val) {
+                    values.push(val);
+                }
+            }
+        }
+
+        if values.len() == n {
+            values.sort();
+            let indices = combination.iter().map(|&i| cells[i].0).collect();
+            subsets.push((indices, values));
+        }
+    }
+    subsets
+}
+
+/// Finds the first naked subset of `n` cells in a component
+pub fn naked_subset<S: SudokuCorrectness>(comp: &S, n: usize) -> Option<Subset> {
+    naked_subsets(comp, n).into_iter().next()
+}
+
+/// Finds every hidden subset of `n` values in a component. Values that are already placed in the
+/// component aren't part of hidden subsets
+pub fn hidden_subsets<S: SudokuCorrectness>(comp: &S, n: usize) -> Vec<Subset> {
+    let cells = comp.indices_and_cells();
+    // the cells that each value can be in
+    let positions: Vec<(u8, Vec<CellIndex>)> = (1..=cells.len() as u8)
+        .map(|val| {
+            let indices = cells
+                .iter()
+                .filter(|(_, cell)| cell.as_value().is_none() && cell.is_or_maybe(val))
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>();
+            (val, indices)
+        })
+        .filter(|(_, indices)| !indices.is_empty())
+        .collect();
+
+    let mut subsets = vec![];
+    for combination in combinations(positions.len(), n) {
+        let mut indices: Vec<CellIndex> = vec![];
+        for &i in &combination {
+            for index in &positions[i].1 {
+                if !indices.contains(index) {
+                    indices.push(*index);
+                }
+            }
+        }
+
+        if indices.len() == n {
+            let values = combination.iter().map(|&i| positions[i].0).collect();
+            subsets.push((indices, values));
+        }
+    }
+    subsets
+}
+
+/// Finds the first hidden subset of `n` values in a component
+pub fn hidden_subset<S: SudokuCorrectness>(comp: &S, n: usize) -> Option<Subset> {
+    hidden_subsets(comp, n).into_iter().next()
+}
+
+/// Removes the values of a naked subset from the maybes of every other cell in the component.
+///
+/// Returns the new board if any maybes were removed
+pub fn enforce_naked_subset<S: SudokuCorrectness>(
+    board: &GameBoard,
+    comp: &S,
+    (cells, values): &Subset,
+) -> Option<GameBoard> {
+    let mut next_board = board.clone();
+    let mut changed = false;
+    for (index, cell) in comp.indices_and_cells() {
+        if cells.contains(&index) {
+            continue;
+        }
+        for maybe in cell.maybe_values().unwrap_or_default() {
+            if values.contains(&maybe) {
+                next_board.remove_maybe(index, maybe);
+                changed = true;
+            }
+        }
+    }
+
+    if changed {
+        Some(next_board)
+    } else {
+        None
+    }
+}
+
+/// Removes every value other than the values of a hidden subset from the maybes of the cells of
+/// the subset.
+///
+/// Returns the new board if any maybes were removed
+pub fn enforce_hidden_subset(board: &GameBoard, (cells, values): &Subset) -> Option<GameBoard> {
+    let mut next_board = board.clone();
+    let mut changed = false;
+    for &index in cells {
+        for maybe in board[index].maybe_values().unwrap_or_default() {
+            if !values.contains(&maybe) {
+                next_board.remove_maybe(index, maybe);
+                changed = true;
+            }
+        }
+    }
+
+    if changed {
+        Some(next_board)
+    } else {
+        None
+    }
+}
+
+/// Enforces the first naked subset of `n` cells in any row, column, or house that changes the
+/// board. Returns `None` if no naked subset changes the board
+pub fn apply_naked_subset(board: &GameBoard, n: usize) -> Option<GameBoard> {
+    for row in board.rows() {
+        for subset in naked_subsets(&row, n) {
+            if let Some(ret) = enforce_naked_subset(board, &row, &subset) {
+                return Some(ret);
+            }
+        }
+    }
+
+    for col in board.columns() {
+        for subset in naked_subsets(&col, n) {
+            if let Some(ret) = enforce_naked_subset(board, &col, &subset) {
+                return Some(ret);
+            }
+        }
+    }
+
+    for house in board.houses() {
+        for subset in naked_subsets(&house, n) {
+            if let Some(ret) = enforce_naked_subset(board, &house, &subset) {
+                return Some(ret);
+            }
+        }
+    }
+
+    None
+}
+
+/// Enforces the first hidden subset of `n` values in any row, column, or house that changes the
+/// board. Returns `None` if no hidden subset changes the board
+pub fn apply_hidden_subset(board: &GameBoard, n: usize) -> Option<GameBoard> {
+    for row in board.rows() {
+        for subset in hidden_subsets(&row, n) {
+            if let Some(ret) = enforce_hidden_subset(board, &subset) {
+                return Some(ret);
+            }
+        }
+    }
+
+    for col in board.columns() {
+        for subset in hidden_subsets(&col, n) {
+            if let Some(ret) = enforce_hidden_subset(board, &subset) {
+                return Some(ret);
+            }
+        }
+    }
+
+    for house in board.houses() {
+        for subset in hidden_subsets(&house, n) {
+            if let Some(ret) = enforce_hidden_subset(board, &subset) {
+                return Some(ret);
+            }
+        }
+    }
+
+    None
+}
+
+/// Every way to choose `k` of the indices `0..n`, in increasing order
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    fn helper(
+        start: usize,
+        n: usize,
+        k: usize,
+        current: &mut Vec<usize>,
+        out: &mut Vec<Vec<usize>>,
+    ) {
+        if current.len() == k {
+            out.push(current.clone());
+            return;
+        }
+        for i in start..n {
+            current.push(i);
+            helper(i + 1, n, k, current, out);
+            current.pop();
+        }
+    }
+
+    let mut out = vec![];
+    helper(0, n, k, &mut vec![], &mut out);
+    out
+}
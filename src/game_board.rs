@@ -1,23 +1,175 @@
 //! Game board logic
 
+use crate::advanced_solver::techniques::{HiddenSingle, NakedSingle, Technique};
+use crate::advanced_solver::entropy::Entropy;
+use crate::advanced_solver::{Difficulty, Solver};
 use crate::game_board_controller::NoteMode;
-use crate::validity::{SolutionsTree, SudokuCorrectness, SudokuCorrectnessMut};
-use std::collections::{HashSet, HashMap};
+use crate::game_creator::{JSONCellEntry, BYTE_STRING_TERMINATOR};
+use crate::validity::{
+    count_solutions, SolutionsTree, SolveOutcome, SudokuCorrectness, SudokuCorrectnessMut,
+};
+use std::collections::HashSet;
 use std::iter::FromIterator;
 use std::ops::{Deref, DerefMut, Index, IndexMut};
-use std::fmt::{Debug, Formatter};
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
 
-/// The size of the game board
+/// The size of a standard game board
 pub const SIZE: usize = 9;
 
-#[derive(Clone)]
+/// The size of the largest supported game board
+pub const MAX_SIZE: usize = 16;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 /// Stores game board information
 pub struct GameBoard {
     /// Stores the contents of the cells.
     /// 0 is an empty cell
-    pub cells: [[CellValue; SIZE]; SIZE],
+    pub cells: Vec<Vec<CellValue>>,
+    house_width: usize,
+    house_height: usize,
+    variant: GameBoardVariant,
+    #[serde(skip, default = "auto_remove_default")]
+    auto_remove: bool,
+}
+
+fn auto_remove_default() -> bool {
+    true
+}
+
+/// The set of rules that a game board is played with
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameBoardVariant {
+    /// Every row, column, and house must contain each value once
+    Standard,
+    /// Like standard, but both main diagonals must also contain each value once. Also known as
+    /// X-Sudoku
+    Diagonal,
+}
+
+/// The kinds of groups of cells that can't contain the same value twice
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UnitKind {
+    /// A row
+    Row,
+    /// A column
+    Column,
+    /// A house
+    House,
+    /// A diagonal of a diagonal board
+    Diagonal,
+}
+
+/// A value that appears more than once in the same unit
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    /// The cells in the unit that have the value
+    pub cells: Vec<CellIndex>,
+    /// The kind of unit the cells are in
+    pub unit: UnitKind,
+    /// The repeated value
+    pub digit: u8,
+}
+
+/// How the cells a player fills in compare to the solution. Preset cells aren't graded
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Grade {
+    /// Cells with the same value as the solution
+    pub correct: Vec<CellIndex>,
+    /// Cells with a different value than the solution
+    pub incorrect: Vec<CellIndex>,
+    /// Cells without a value
+    pub empty: Vec<CellIndex>,
+}
+
+/// A step taken by the brute force solver, recorded by [solve_traced](GameBoard::solve_traced)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// A value was placed in a cell
+    Try {
+        /// The cell the value was placed in
+        cell: CellIndex,
+        /// The value placed
+        value: u8,
+        /// How many guesses the value was placed on top of
+        depth: usize,
+    },
+    /// A value that was tried in a cell couldn't lead to a solution, and was taken back out
+    Backtrack {
+        /// The cell the value was removed from
+        cell: CellIndex,
+        /// The value removed
+        value: u8,
+        /// How many guesses the value was placed on top of
+        depth: usize,
+    },
+}
+
+/// The reasons a preset can't be placed on a board
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresetError {
+    /// The cell is outside of the board
+    OutOfBounds(CellIndex),
+    /// The value isn't between 1 and the size of the board
+    InvalidValue(CellIndex, u8),
+}
+
+impl Display for PresetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for PresetError {}
+
+/// The fewest givens a standard board can have while still having only one solution
+pub const MIN_GIVENS: usize = 17;
+
+/// The reasons a board isn't a proper puzzle, found by [validate_puzzle](GameBoard::validate_puzzle)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PuzzleProblem {
+    /// A value is used more than once in the same unit
+    Invalid,
+    /// The puzzle can't be solved
+    NoSolution,
+    /// The puzzle can be solved in more than one way
+    MultipleSolutions,
+    /// A standard board has fewer than [MIN_GIVENS] givens, so it can't have only one solution
+    TooFewGivens,
+}
+
+impl Display for PuzzleProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for PuzzleProblem {}
+
+/// The bit set in a byte from [to_bytes](GameBoard::to_bytes) when the cell is a preset
+pub const PRESET_BIT: u8 = 0b1000_0000;
+
+/// The reasons bytes can't be read as a board by [from_bytes](GameBoard::from_bytes)
+#[derive(Debug, Clone, PartialEq)]
+pub enum BytesError {
+    /// There weren't exactly one byte per cell of a standard board
+    WrongLength(usize),
+    /// The byte for the cell isn't empty or a value between 1 and 9, with or without the
+    /// [PRESET_BIT]
+    InvalidByte(CellIndex, u8),
+}
+
+impl Display for BytesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
 }
 
+impl Error for BytesError {}
+
 /// Type for the row index
 pub type RowIndex = usize;
 /// Type for the column index
@@ -41,12 +193,10 @@ impl<'a> Deref for Column<'a> {
 }
 
 impl SudokuCorrectness for Column<'_> {
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
-        self.cells
-            .iter()
-            .enumerate()
-            .map(|(index, &cell)| ((self.col_n, index), cell))
-            .collect()
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        for (index, &cell) in self.cells.iter().enumerate() {
+            visitor((self.col_n, index), cell);
+        }
     }
 }
 
@@ -63,18 +213,13 @@ impl<'a> ColumnMut<'a> {
 
     /// Gets the mutable cell at an index
     pub fn cell_mut(&mut self, row: usize) -> Option<&mut CellValue> {
-        match row {
-            0..=8 => self.board.cells[row].get_mut(self.col_n),
-            _ => None,
-        }
+        let col_n = self.col_n;
+        self.board.cells.get_mut(row).and_then(move |row| row.get_mut(col_n))
     }
 
     /// Gets the cell at an index
     pub fn cell(&self, row: usize) -> Option<&CellValue> {
-        match row {
-            0..=8 => self.board.cells[row].get(self.col_n),
-            _ => None,
-        }
+        self.board.cells.get(row).and_then(|row| row.get(self.col_n))
     }
 
     /// Gets the cells within the column
@@ -98,14 +243,10 @@ impl IndexMut<usize> for ColumnMut<'_> {
 }
 
 impl SudokuCorrectness for ColumnMut<'_> {
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
-        let col_n = self.col_n;
-        self.board
-            .cells
-            .iter()
-            .enumerate()
-            .map(|(index, row)| ((col_n, index), &row[col_n]))
-            .collect()
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        for (index, row) in self.board.cells.iter().enumerate() {
+            visitor((self.col_n, index), &row[self.col_n]);
+        }
     }
 }
 
@@ -124,32 +265,30 @@ impl SudokuCorrectnessMut for ColumnMut<'_> {
 /// Row type
 pub struct Row<'a> {
     /// The cells within the row
-    pub cells: &'a [CellValue; SIZE],
+    pub cells: &'a [CellValue],
     row_n: usize,
 }
 
 impl Deref for Row<'_> {
-    type Target = [CellValue; SIZE];
+    type Target = [CellValue];
 
     fn deref(&self) -> &Self::Target {
-        &self.cells
+        self.cells
     }
 }
 
 impl SudokuCorrectness for Row<'_> {
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
-        self.cells
-            .iter()
-            .enumerate()
-            .map(|(index, cell)| ((index, self.row_n), cell))
-            .collect()
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        for (index, cell) in self.cells.iter().enumerate() {
+            visitor((index, self.row_n), cell);
+        }
     }
 }
 
 /// Mutable row type
 pub struct RowMut<'a> {
     /// The cells within the row
-    pub cells: &'a mut [CellValue; SIZE],
+    pub cells: &'a mut [CellValue],
     row_n: usize,
 }
 
@@ -164,26 +303,24 @@ impl<'a> RowMut<'a> {
 }
 
 impl Deref for RowMut<'_> {
-    type Target = [CellValue; SIZE];
+    type Target = [CellValue];
 
     fn deref(&self) -> &Self::Target {
-        &self.cells
+        self.cells
     }
 }
 
 impl DerefMut for RowMut<'_> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.cells
+        self.cells
     }
 }
 
 impl SudokuCorrectness for RowMut<'_> {
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
-        self.cells
-            .iter()
-            .enumerate()
-            .map(|(index, cell)| ((index, self.row_n), cell))
-            .collect()
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        for (index, cell) in self.cells.iter().enumerate() {
+            visitor((index, self.row_n), cell);
+        }
     }
 }
 
@@ -215,19 +352,12 @@ impl<'a> Deref for House<'a> {
 }
 
 impl SudokuCorrectness for House<'_> {
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
-        self.cells
-            .iter()
-            .enumerate()
-            .map(|(row_n, &row)| {
-                let true_row = self.house_first_y + row_n;
-                row.iter().enumerate().map(move |(col_n, cell)| {
-                    let true_col = self.house_first_x + col_n;
-                    ((true_col, true_row), cell)
-                })
-            })
-            .flatten()
-            .collect()
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        for (row_n, &row) in self.cells.iter().enumerate() {
+            for (col_n, cell) in row.iter().enumerate() {
+                visitor((self.house_first_x + col_n, self.house_first_y + row_n), cell);
+            }
+        }
     }
 }
 
@@ -242,8 +372,8 @@ impl<'a> HouseMut<'a> {
     /// Gets the cells within the house
     pub fn cells(&self) -> impl IntoIterator<Item = &CellValue> {
         let mut ret = vec![];
-        for j in 0..3 {
-            for i in 0..3 {
+        for j in 0..self.board.house_height {
+            for i in 0..self.board.house_width {
                 let x = self.house_first_x + i;
                 let y = self.house_first_y + j;
                 let cell = self.board.cell_value((x, y));
@@ -253,22 +383,18 @@ impl<'a> HouseMut<'a> {
         ret
     }
 
-    /// Gets a cell in the house, treated as a 3,3 array
+    /// Gets a cell in the house, treated as a house width by house height array
     pub fn cell(&self, x: usize, y: usize) -> Option<&CellValue> {
         let x = self.house_first_x + x;
         let y = self.house_first_y + y;
-        self.board.cells.get(y).map(move |row| row.get(x)).flatten()
+        self.board.cells.get(y).and_then(move |row| row.get(x))
     }
 
-    /// Gets the mutable cell in the house, treated as a 3,3 array
+    /// Gets the mutable cell in the house, treated as a house width by house height array
     pub fn mut_cell(&mut self, x: usize, y: usize) -> Option<&mut CellValue> {
         let x = self.house_first_x + x;
         let y = self.house_first_y + y;
-        self.board
-            .cells
-            .get_mut(y)
-            .map(move |row| row.get_mut(x))
-            .flatten()
+        self.board.cells.get_mut(y).and_then(move |row| row.get_mut(x))
     }
 }
 
@@ -276,41 +402,29 @@ impl<'a> Index<usize> for HouseMut<'a> {
     type Output = [CellValue];
 
     fn index(&self, index: usize) -> &Self::Output {
-        let adjusted = &self.board.cells[self.house_first_y..(self.house_first_y + 3)];
-        let row = &adjusted[index][self.house_first_x..(self.house_first_x + 3)];
-        row
+        let (width, height) = (self.board.house_width, self.board.house_height);
+        let adjusted = &self.board.cells[self.house_first_y..(self.house_first_y + height)];
+        &adjusted[index][self.house_first_x..(self.house_first_x + width)]
     }
 }
 
 impl<'a> IndexMut<usize> for HouseMut<'a> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        let adjusted = &mut self.board.cells[self.house_first_y..(self.house_first_y + 3)];
-        let row = &mut adjusted[index][self.house_first_x..(self.house_first_x + 3)];
-        row
+        let (width, height) = (self.board.house_width, self.board.house_height);
+        let adjusted = &mut self.board.cells[self.house_first_y..(self.house_first_y + height)];
+        &mut adjusted[index][self.house_first_x..(self.house_first_x + width)]
     }
 }
 
 impl SudokuCorrectness for HouseMut<'_> {
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
-        self.board
-            .cells
-            .iter()
-            .skip(self.house_first_y) // skip to the first in the house
-            .take(3) // only take 3
-            .enumerate()
-            .map(|(row_n, row)| {
-                let true_row = self.house_first_y + row_n;
-                row.iter()
-                    .skip(self.house_first_x) // skip to the first in the row
-                    .take(3) // only take 3
-                    .enumerate()
-                    .map(move |(col_n, cell)| {
-                        let true_col = self.house_first_x + col_n;
-                        ((true_col, true_row), cell)
-                    })
-            })
-            .flatten()
-            .collect()
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        let rows = self.house_first_y..(self.house_first_y + self.board.house_height);
+        let columns = self.house_first_x..(self.house_first_x + self.board.house_width);
+        for row in rows {
+            for column in columns.clone() {
+                visitor((column, row), &self.board.cells[row][column]);
+            }
+        }
     }
 }
 
@@ -318,30 +432,91 @@ impl SudokuCorrectnessMut for HouseMut<'_> {
     fn indices_and_cells_mut(&mut self) -> Vec<(CellIndex, &mut CellValue)> {
         let house_first_y = self.house_first_y;
         let house_first_x = self.house_first_x;
+        let house_width = self.board.house_width;
+        let house_height = self.board.house_height;
         self.board
             .cells
             .iter_mut()
-            .skip(self.house_first_y) // skip to the first in the house
-            .take(3) // only take 3
+            .skip(house_first_y) // skip to the first in the house
+            .take(house_height) // only take the height of the house
             .enumerate()
-            .map(|(row_n, row)| {
+            .flat_map(|(row_n, row)| {
                 let true_row = house_first_y + row_n;
 
                 row.iter_mut()
                     .skip(house_first_x) // skip to the first in the row
-                    .take(3) // only take 3
+                    .take(house_width) // only take the width of the house
                     .enumerate()
                     .map(move |(col_n, cell)| {
                         let true_col = house_first_x + col_n;
                         ((true_col, true_row), cell)
                     })
             })
-            .flatten()
             .collect()
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+/// Diagonal type, only used as a rule by boards with the diagonal variant
+pub struct Diagonal<'a> {
+    /// The cells within the diagonal, ordered from the top row to the bottom row
+    pub cells: Vec<&'a CellValue>,
+    diagonal_n: usize,
+}
+
+impl<'a> Deref for Diagonal<'a> {
+    type Target = Vec<&'a CellValue>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cells
+    }
+}
+
+impl SudokuCorrectness for Diagonal<'_> {
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        let size = self.cells.len();
+        for (row, &cell) in self.cells.iter().enumerate() {
+            let column = if self.diagonal_n == 0 { row } else { size - 1 - row };
+            visitor((column, row), cell);
+        }
+    }
+}
+
+/// Any group of cells that can't contain the same value twice
+pub enum Unit<'a> {
+    /// A row
+    Row(Row<'a>),
+    /// A column
+    Column(Column<'a>),
+    /// A house
+    House(House<'a>),
+    /// A diagonal of a diagonal board
+    Diagonal(Diagonal<'a>),
+}
+
+impl Unit<'_> {
+    /// The kind of unit this is
+    pub fn kind(&self) -> UnitKind {
+        match self {
+            Unit::Row(_) => UnitKind::Row,
+            Unit::Column(_) => UnitKind::Column,
+            Unit::House(_) => UnitKind::House,
+            Unit::Diagonal(_) => UnitKind::Diagonal,
+        }
+    }
+}
+
+impl SudokuCorrectness for Unit<'_> {
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        match self {
+            Unit::Row(row) => row.visit_cells(visitor),
+            Unit::Column(column) => column.visit_cells(visitor),
+            Unit::House(house) => house.visit_cells(visitor),
+            Unit::Diagonal(diagonal) => diagonal.visit_cells(visitor),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 /// The possible values that a cell can have
 pub enum CellValue {
     /// A value present at the beginning of a sudoku game. Can not be changed
@@ -350,8 +525,10 @@ pub enum CellValue {
     Value(u8),
     /// Possible values set by the user
     Notes {
-        /// All values of the board can have a status
-        status: [Option<NoteStatus>; 9],
+        /// The values the cell may be, where bit `val - 1` is set for `val`
+        maybe: u16,
+        /// The values the cell can't be, where bit `val - 1` is set for `val`
+        deny: u16,
     },
     /// The cell is empty
     Empty,
@@ -371,14 +548,22 @@ impl CellValue {
     /// Gets the values that this cell could be
     pub fn maybe_values(&self) -> Option<Vec<u8>> {
         match self {
-            CellValue::Notes { status } => {
-                let mut ret = vec![];
-                for val_status in status.iter().enumerate().map(|(i, s)| (i as u8 + 1, s)) {
-                    if let (val, Some(NoteStatus::Maybe)) = val_status {
-                        ret.push(val);
-                    }
+            &CellValue::Notes { maybe, .. } => Some(mask_values(maybe)),
+            _ => None,
+        }
+    }
+
+    /// Gets the status of a value in the notes of this cell, if it has one
+    pub fn note_status(&self, val: u8) -> Option<NoteStatus> {
+        match self {
+            &CellValue::Notes { maybe, deny } => {
+                if maybe & note_bit(val) != 0 {
+                    Some(NoteStatus::Maybe)
+                } else if deny & note_bit(val) != 0 {
+                    Some(NoteStatus::Deny)
+                } else {
+                    None
                 }
-                Some(ret)
             }
             _ => None,
         }
@@ -389,7 +574,7 @@ impl CellValue {
         match self {
             CellValue::Preset(v) => *v == val,
             CellValue::Value(v) => *v == val,
-            CellValue::Notes { status } => status[(val - 1) as usize] == Some(NoteStatus::Maybe),
+            CellValue::Notes { maybe, .. } => maybe & note_bit(val) != 0,
             CellValue::Empty => false,
         }
     }
@@ -397,22 +582,37 @@ impl CellValue {
     /// Gets the values that cell can't be
     fn denied_values(&self) -> Option<Vec<u8>> {
         match self {
-            CellValue::Notes { status } => {
-                let mut ret = vec![];
-                for val_status in status.iter().enumerate().map(|(i, s)| (i as u8 + 1, s)) {
-                    if let (val, Some(NoteStatus::Deny)) = val_status {
-                        ret.push(val);
-                    }
-                }
-                Some(ret)
-            }
+            &CellValue::Notes { deny, .. } => Some(mask_values(deny)),
             _ => None,
         }
     }
+
+    /// Removes a value from the notes of this cell, whether it's a maybe or a deny
+    fn clear_note(&mut self, val: u8) {
+        if let CellValue::Notes { maybe, deny } = self {
+            *maybe &= !note_bit(val);
+            *deny &= !note_bit(val);
+        }
+    }
+}
+
+/// The bit for a value in a notes mask. Values that can't be noted, such as 0, have no bit
+fn note_bit(val: u8) -> u16 {
+    match val as usize {
+        1..=MAX_SIZE => 1 << (val - 1),
+        _ => 0,
+    }
+}
+
+/// The values whose bits are set in a notes mask, in ascending order
+fn mask_values(mask: u16) -> Vec<u8> {
+    (1..=MAX_SIZE as u8)
+        .filter(|&val| mask & note_bit(val) != 0)
+        .collect()
 }
 
 /// Whether or not this note is number is maybe or deny
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NoteStatus {
     /// This cell can be this value
     Maybe,
@@ -420,35 +620,288 @@ pub enum NoteStatus {
     Deny,
 }
 
+impl Default for GameBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GameBoard {
-    /// Creates a new game board
+    /// Creates a new standard game board, made up of 3x3 houses
     pub fn new() -> Self {
+        Self::with_house_size(3, 3)
+    }
+
+    /// Creates a new game board made up of houses of the given dimensions. The board has as many
+    /// rows and columns as there are cells in a house, so 2x2 houses create a 4x4 board and 4x4
+    /// houses create a 16x16 board.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board would be empty or larger than [MAX_SIZE]
+    ///
+    /// [MAX_SIZE]: crate::game_board::MAX_SIZE
+    pub fn with_house_size(house_width: usize, house_height: usize) -> Self {
+        let size = house_width * house_height;
+        assert!(
+            size > 0 && size <= MAX_SIZE,
+            "Invalid board size: {}x{} houses",
+            house_width,
+            house_height
+        );
         Self {
-            cells: [[CellValue::Empty; SIZE]; SIZE],
+            cells: vec![vec![CellValue::Empty; size]; size],
+            house_width,
+            house_height,
+            variant: GameBoardVariant::Standard,
+            auto_remove: auto_remove_default(),
         }
     }
 
-    /// Sets preset, immutable cells within the board
-    pub fn with_presets<I>(mut self, presets: I) -> Self
+    /// Sets the rules that the board is played with
+    pub fn with_variant(mut self, variant: GameBoardVariant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    /// The rules that the board is played with
+    pub fn variant(&self) -> GameBoardVariant {
+        self.variant
+    }
+
+    /// Sets whether setting a value removes that value from the notes of the cells in the same
+    /// row, column, and house. Enabled by default
+    pub fn with_auto_remove(mut self, auto_remove: bool) -> Self {
+        self.auto_remove = auto_remove;
+        self
+    }
+
+    /// Sets whether setting a value removes that value from the notes of the cells in the same
+    /// row, column, and house
+    pub fn set_auto_remove(&mut self, auto_remove: bool) {
+        self.auto_remove = auto_remove;
+    }
+
+    /// Whether setting a value removes that value from the notes of the cells in the same row,
+    /// column, and house
+    pub fn auto_remove(&self) -> bool {
+        self.auto_remove
+    }
+
+    /// The number of rows and columns in the board, which is also the largest value of a cell
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.house_width * self.house_height
+    }
+
+    /// The number of columns in a house
+    #[inline]
+    pub fn house_width(&self) -> usize {
+        self.house_width
+    }
+
+    /// The number of rows in a house
+    #[inline]
+    pub fn house_height(&self) -> usize {
+        self.house_height
+    }
+
+    /// Sets preset, immutable cells within the board.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a preset is outside of the board or its value isn't between 1 and the size of the
+    /// board. Use [try_with_presets](GameBoard::try_with_presets) to check the presets instead.
+    pub fn with_presets<I>(self, presets: I) -> Self
+    where
+        I: IntoIterator<Item = ((usize, usize), u8)>,
+    {
+        self.try_with_presets(presets)
+            .unwrap_or_else(|e| panic!("Invalid preset: {}", e))
+    }
+
+    /// Sets preset, immutable cells within the board, checking that each preset is inside of the
+    /// board and has a value between 1 and the size of the board
+    pub fn try_with_presets<I>(mut self, presets: I) -> Result<Self, PresetError>
     where
         I: IntoIterator<Item = ((usize, usize), u8)>,
     {
+        let size = self.size();
         for ((x, y), val) in presets {
+            if x >= size || y >= size {
+                return Err(PresetError::OutOfBounds((x, y)));
+            }
+            if val == 0 || val as usize > size {
+                return Err(PresetError::InvalidValue((x, y), val));
+            }
             self.cells[y][x] = CellValue::Preset(val);
         }
-        self
+        Ok(self)
+    }
+
+    /// Every value that appears more than once in a unit, along with which unit it's repeated in.
+    /// A cell can be part of multiple conflicts
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.units()
+            .flat_map(|unit| self.conflicts_in(unit.kind(), &unit))
+            .collect()
+    }
+
+    fn conflicts_in(&self, unit: UnitKind, component: &dyn SudokuCorrectness) -> Vec<Conflict> {
+        let mut conflicts: Vec<Conflict> = vec![];
+        for index in component.invalid_cells() {
+            let digit = self[index].as_value().unwrap();
+            match conflicts.iter_mut().find(|conflict| conflict.digit == digit) {
+                Some(conflict) => conflict.cells.push(index),
+                None => conflicts.push(Conflict {
+                    cells: vec![index],
+                    unit,
+                    digit,
+                }),
+            }
+        }
+        conflicts
+    }
+
+    /// Checks whether placing a value in a cell would conflict with another cell in the same row,
+    /// column, house, or diagonal, for the diagonal variant, without changing the board. The
+    /// current value of the cell is ignored
+    pub fn would_conflict(&self, ind: CellIndex, val: u8) -> bool {
+        let affected = AffectedComponents::new(self, ind);
+        let conflicts = |component: &dyn SudokuCorrectness| {
+            component
+                .indices_and_values()
+                .into_iter()
+                .any(|(index, value)| index != ind && value == val)
+        };
+
+        conflicts(&affected.row())
+            || conflicts(&affected.column())
+            || conflicts(&affected.house())
+            || affected.diagonals().iter().any(|diagonal| conflicts(diagonal))
+    }
+
+    /// The values that can be placed in a cell without conflicting with a value in the same row,
+    /// column, house, or diagonal, for the diagonal variant. Cells that already have a value have
+    /// no candidates
+    pub fn candidates(&self, ind: CellIndex) -> Vec<u8> {
+        if self.cell_value(ind).as_value().is_some() {
+            return vec![];
+        }
+
+        let used = self.used_values(ind);
+        (1..=self.size() as u8)
+            .filter(|val| used & (1 << (val - 1)) == 0)
+            .collect()
+    }
+
+    /// Every value that can be placed in every cell without a value, ordered by row, then column,
+    /// then value. Uses the [candidates](GameBoard::candidates) of each cell, so notes are ignored
+    pub fn all_candidate_moves(&self) -> Vec<(CellIndex, u8)> {
+        self.iter_unset()
+            .into_iter()
+            .flat_map(|cell| {
+                self.candidates(cell)
+                    .into_iter()
+                    .map(move |value| (cell, value))
+            })
+            .collect()
+    }
+
+    /// Every cell without a value that has exactly one [candidate](GameBoard::candidates), and that
+    /// candidate, ordered by row then column
+    pub fn naked_singles(&self) -> Vec<(CellIndex, u8)> {
+        self.iter_unset()
+            .into_iter()
+            .filter_map(|cell| match self.candidates(cell)[..] {
+                [value] => Some((cell, value)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A bitmask of the values in the same row, column, house, or diagonal as a cell, where bit
+    /// `val - 1` is set if `val` is used
+    fn used_values(&self, (column, row): CellIndex) -> u32 {
+        let size = self.size();
+        let mut used = 0u32;
+        let mut add = |cell: &CellValue| {
+            if let Some(val) = cell.as_value() {
+                used |= 1 << (val - 1);
+            }
+        };
+
+        for i in 0..size {
+            add(&self.cells[row][i]);
+            add(&self.cells[i][column]);
+        }
+
+        let first_row = row / self.house_height * self.house_height;
+        let first_column = column / self.house_width * self.house_width;
+        for house_row in &self.cells[first_row..(first_row + self.house_height)] {
+            for cell in &house_row[first_column..(first_column + self.house_width)] {
+                add(cell);
+            }
+        }
+
+        if self.variant == GameBoardVariant::Diagonal {
+            for i in 0..size {
+                if row == column {
+                    add(&self.cells[i][i]);
+                }
+                if row + column == size - 1 {
+                    add(&self.cells[i][size - 1 - i]);
+                }
+            }
+        }
+
+        used
+    }
+
+    /// The cells that share a row, column, or house with a cell, not including the cell itself.
+    /// On a 9x9 board, every cell has 20 peers
+    pub fn peers(&self, ind: CellIndex) -> HashSet<CellIndex> {
+        let affected = AffectedComponents::new(self, ind);
+        let mut peers: HashSet<CellIndex> = HashSet::new();
+        let components: [&dyn SudokuCorrectness; 3] =
+            [&affected.row(), &affected.column(), &affected.house()];
+        for component in &components {
+            peers.extend(component.indices_and_cells().into_iter().map(|(i, _)| i));
+        }
+        peers.remove(&ind);
+        peers
+    }
+
+    /// Whether two different cells share a row, column, or house
+    pub fn sees(&self, a: CellIndex, b: CellIndex) -> bool {
+        let same_house = a.0 / self.house_width == b.0 / self.house_width
+            && a.1 / self.house_height == b.1 / self.house_height;
+        a != b && (a.0 == b.0 || a.1 == b.1 || same_house)
     }
 
     /// Gets the character at cell location
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is outside of the board. Use
+    /// [try_cell_value](GameBoard::try_cell_value) to check the cell instead.
     pub fn cell_value(&self, ind: CellIndex) -> &CellValue {
         &self.cells[ind.1][ind.0]
     }
 
+    /// Gets the value of a cell, or `None` if the cell is outside of the board
+    pub fn try_cell_value(&self, ind: CellIndex) -> Option<&CellValue> {
+        self.cells.get(ind.1)?.get(ind.0)
+    }
+
     /// Set cell value.
     ///
-    /// Returns whether a change was made.
+    /// Values that aren't between 1 and the size of the board are ignored.
     pub fn set(&mut self, ind: (usize, usize), mode: &NoteMode, val: u8) {
-        let ref mut cell = self.cells[ind.1][ind.0];
+        if val == 0 || val as usize > self.size() {
+            return;
+        }
+        let cell = &mut self.cells[ind.1][ind.0];
         if let CellValue::Preset(_) = cell {
             return;
         }
@@ -457,83 +910,162 @@ impl GameBoard {
             NoteMode::Value => {
                 *cell = CellValue::Value(val);
 
-                let affected_components = AffectedComponentsMut::new(self, ind);
-                let row_mut = affected_components.row();
-                for cell in row_mut.cells {
-                    if let CellValue::Notes { status } = cell {
-                        status[(val - 1) as usize] = None;
+                // remove the value from the notes of every cell that can now no longer have it
+                if self.auto_remove {
+                    let affected_components = AffectedComponentsMut::new(self, ind);
+                    let row_mut = affected_components.row();
+                    for cell in row_mut.cells {
+                        cell.clear_note(val);
                     }
-                }
 
-                let affected_components = AffectedComponentsMut::new(self, ind);
-                let mut column = affected_components.column();
-                for i in 0..9 {
-                    let cell = column.cell_mut(i).unwrap();
-                    if let CellValue::Notes { status } = cell {
-                        status[(val - 1) as usize] = None;
+                    let size = self.size();
+                    let (house_width, house_height) = (self.house_width, self.house_height);
+
+                    let affected_components = AffectedComponentsMut::new(self, ind);
+                    let mut column = affected_components.column();
+                    for i in 0..size {
+                        let cell = column.cell_mut(i).unwrap();
+                        cell.clear_note(val);
                     }
-                }
 
-                let affected_components = AffectedComponentsMut::new(self, ind);
-                let mut house = affected_components.house();
-                for j in 0..3 {
-                    for i in 0..3 {
-                        let cell = house.mut_cell(i, j).unwrap();
-                        if let CellValue::Notes { status } = cell {
-                            status[(val - 1) as usize] = None;
+                    let affected_components = AffectedComponentsMut::new(self, ind);
+                    let mut house = affected_components.house();
+                    for j in 0..house_height {
+                        for i in 0..house_width {
+                            let cell = house.mut_cell(i, j).unwrap();
+                            cell.clear_note(val);
                         }
                     }
+
+                    let diagonal_cells: Vec<CellIndex> = AffectedComponents::new(self, ind)
+                        .diagonals()
+                        .iter()
+                        .flat_map(|diagonal| diagonal.indices_and_cells())
+                        .map(|(index, _)| index)
+                        .collect();
+                    for index in diagonal_cells {
+                        self[index].clear_note(val);
+                    }
                 }
             }
             NoteMode::Maybe => match cell {
                 CellValue::Preset(_) => {}
                 CellValue::Value(_) => {}
-                CellValue::Notes { status } => {
-                    if let Some(NoteStatus::Maybe) = status[(val - 1) as usize] {
-                        status[(val - 1) as usize] = None;
-                    } else {
-                        status[(val - 1) as usize] = Some(NoteStatus::Maybe);
-                    }
+                CellValue::Notes { maybe, deny } => {
+                    *maybe ^= note_bit(val);
+                    *deny &= !note_bit(val);
                 }
                 CellValue::Empty => {
-                    let mut status = [None; SIZE];
-                    status[(val - 1) as usize] = Some(NoteStatus::Maybe);
-                    *cell = CellValue::Notes { status };
+                    *cell = CellValue::Notes {
+                        maybe: note_bit(val),
+                        deny: 0,
+                    };
                 }
             },
             NoteMode::Deny => match cell {
                 CellValue::Preset(_) => {}
                 CellValue::Value(_) => {}
-                CellValue::Notes { status } => {
-                    if let Some(NoteStatus::Deny) = status[(val - 1) as usize] {
-                        status[(val - 1) as usize] = None;
-                    } else {
-                        status[(val - 1) as usize] = Some(NoteStatus::Deny);
-                    }
+                CellValue::Notes { maybe, deny } => {
+                    *deny ^= note_bit(val);
+                    *maybe &= !note_bit(val);
                 }
                 CellValue::Empty => {
-                    let mut status = [None; SIZE];
-                    status[(val - 1) as usize] = Some(NoteStatus::Deny);
-                    *cell = CellValue::Notes { status };
+                    *cell = CellValue::Notes {
+                        deny: note_bit(val),
+                        maybe: 0,
+                    };
                 }
             },
         }
         //println!("Cell {:?} set to {:?}", ind, cell);
     }
 
-    /// Clears the value in a cell. Can't reset a preset cell
+    /// Marks that a cell may be a value, replacing a deny of that value. Cells with a value aren't
+    /// changed. Unlike [set](GameBoard::set), this never removes the maybe.
+    pub fn add_maybe(&mut self, ind: CellIndex, val: u8) {
+        self.update_notes(ind, |maybe, deny| {
+            *maybe |= note_bit(val);
+            *deny &= !note_bit(val);
+        });
+    }
+
+    /// Removes the maybe of a value from a cell, if it has one
+    pub fn remove_maybe(&mut self, ind: CellIndex, val: u8) {
+        self.update_notes(ind, |maybe, _| *maybe &= !note_bit(val));
+    }
+
+    /// Marks that a cell can't be a value, replacing a maybe of that value. Cells with a value
+    /// aren't changed. Unlike [set](GameBoard::set), this never removes the deny.
+    pub fn add_deny(&mut self, ind: CellIndex, val: u8) {
+        self.update_notes(ind, |maybe, deny| {
+            *deny |= note_bit(val);
+            *maybe &= !note_bit(val);
+        });
+    }
+
+    /// Removes the deny of a value from a cell, if it has one
+    pub fn remove_deny(&mut self, ind: CellIndex, val: u8) {
+        self.update_notes(ind, |_, deny| *deny &= !note_bit(val));
+    }
+
+    /// Changes the maybe and deny masks of a cell without a value. Empty cells only become noted
+    /// if a note is added
+    fn update_notes<F: FnOnce(&mut u16, &mut u16)>(&mut self, ind: CellIndex, update: F) {
+        let cell = &mut self.cells[ind.1][ind.0];
+        let (mut maybe, mut deny) = match *cell {
+            CellValue::Notes { maybe, deny } => (maybe, deny),
+            CellValue::Empty => (0, 0),
+            _ => return,
+        };
+        update(&mut maybe, &mut deny);
+        if *cell == CellValue::Empty && maybe == 0 && deny == 0 {
+            return;
+        }
+        *cell = CellValue::Notes { maybe, deny };
+    }
+
+    /// Clears the value in a cell. Can't reset a preset cell.
+    ///
+    /// If setting values removes them from notes, the cleared value is added back as a maybe to the
+    /// noted cells in the same row, column, house, or diagonal that can have it again, unless it's
+    /// denied there.
     pub fn reset(&mut self, ind: (usize, usize)) {
-        match self.cells[ind.1][ind.0] {
+        let old = self.cells[ind.1][ind.0];
+        match old {
             CellValue::Preset(_) => {}
             _all => {
                 self.cells[ind.1][ind.0] = CellValue::Empty;
                 //println!("Cell {:?} set to {:?}", ind, self.cells[ind.1][ind.0]);
             }
         }
+
+        if let (CellValue::Value(val), true) = (old, self.auto_remove) {
+            let mut affected = self.peers(ind);
+            for diagonal in AffectedComponents::new(self, ind).diagonals() {
+                affected.extend(diagonal.indices_and_cells().into_iter().map(|(index, _)| index));
+            }
+            for index in affected {
+                let cell = self.cell_value(index);
+                if let CellValue::Notes { .. } = cell {
+                    if cell.note_status(val).is_none() && self.candidates(index).contains(&val) {
+                        self.add_maybe(index, val);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Clears every cell that isn't a preset, restarting the board from its givens
+    pub fn clear_user_values(&mut self) {
+        for cell in self.cells.iter_mut().flatten() {
+            if !matches!(cell, CellValue::Preset(_)) {
+                *cell = CellValue::Empty;
+            }
+        }
     }
 
     /// Gets a row from the board
-    pub fn row(&self, index: usize) -> Option<Row> {
+    pub fn row(&self, index: usize) -> Option<Row<'_>> {
         self.cells.get(index).map(|raw_row| Row {
             cells: raw_row,
             row_n: index,
@@ -541,7 +1073,7 @@ impl GameBoard {
     }
 
     /// Gets a mutable row from the board
-    pub fn row_mut(&mut self, index: usize) -> Option<RowMut> {
+    pub fn row_mut(&mut self, index: usize) -> Option<RowMut<'_>> {
         self.cells.get_mut(index).map(|raw_row| RowMut {
             cells: raw_row,
             row_n: index,
@@ -549,121 +1081,144 @@ impl GameBoard {
     }
 
     /// Gets a column from the board
-    pub fn column(&self, index: usize) -> Option<Column> {
-        match index {
-            0..=8 => {
-                let mut ret = vec![];
+    pub fn column(&self, index: usize) -> Option<Column<'_>> {
+        if index < self.size() {
+            let mut ret = vec![];
 
-                for row in 0..9 {
-                    let ref cell = self.cells[row][index];
-                    ret.push(cell);
-                }
+            for row in 0..self.size() {
+                ret.push(&self.cells[row][index]);
+            }
 
-                Some(Column {
-                    cells: ret,
-                    col_n: index,
-                })
-            }
-            _ => None,
+            Some(Column {
+                cells: ret,
+                col_n: index,
+            })
+        } else {
+            None
         }
     }
 
     /// Gets a column of mutable cells from the board
-    pub fn column_mut(&mut self, index: usize) -> Option<ColumnMut> {
-        match index {
-            0..=8 => Some(ColumnMut::new(self, index)),
-            _ => None,
+    pub fn column_mut(&mut self, index: usize) -> Option<ColumnMut<'_>> {
+        if index < self.size() {
+            Some(ColumnMut::new(self, index))
+        } else {
+            None
         }
     }
 
-    /// Gets the specified house, where houses are indexed as a 2D array of size 3,3
-    pub fn house(&self, x: usize, y: usize) -> Option<House> {
-        match (x, y) {
-            (0..=2, 0..=2) => {
-                let mut ret = vec![];
-                let start_row = x * 3;
-                let start_column = y * 3;
-                let column_range = start_column..(start_column + 3);
-
-                for j in 0..3 {
-                    let ref row = self.cells[start_row + j][column_range.clone()];
-                    ret.push(row);
-                }
-
-                Some(House {
-                    cells: ret,
-                    house_first_x: start_column,
-                    house_first_y: start_row,
-                })
+    /// Gets the specified house, where houses are indexed as a 2D array with as many rows as a
+    /// house is wide and as many columns as a house is tall.
+    ///
+    /// Unlike a [CellIndex], the row comes first, so the house of the cell `(column, row)` is
+    /// `house(row / house_height, column / house_width)`.
+    pub fn house(&self, house_row: usize, house_column: usize) -> Option<House<'_>> {
+        if house_row < self.house_width && house_column < self.house_height {
+            let mut ret = vec![];
+            let start_row = house_row * self.house_height;
+            let start_column = house_column * self.house_width;
+            let column_range = start_column..(start_column + self.house_width);
+
+            for j in 0..self.house_height {
+                ret.push(&self.cells[start_row + j][column_range.clone()]);
             }
-            _ => None,
+
+            Some(House {
+                cells: ret,
+                house_first_x: start_column,
+                house_first_y: start_row,
+            })
+        } else {
+            None
         }
     }
 
-    /// Gets the specified house of mutable cells, where houses are indexed as a 2D array of size 3,3
-    pub fn house_mut(&mut self, x: usize, y: usize) -> Option<HouseMut> {
-        match (x, y) {
-            (0..=2, 0..=2) => {
-                let start_row = x * 3;
-                let start_column = y * 3;
-
-                Some(HouseMut {
-                    board: self,
-                    house_first_x: start_column,
-                    house_first_y: start_row,
-                })
-            }
-            _ => None,
+    /// Gets the specified house of mutable cells, where houses are indexed the same way as
+    /// [house](GameBoard::house), with the row first
+    pub fn house_mut(
+        &mut self,
+        house_row: usize,
+        house_column: usize,
+    ) -> Option<HouseMut<'_>> {
+        if house_row < self.house_width && house_column < self.house_height {
+            let start_row = house_row * self.house_height;
+            let start_column = house_column * self.house_width;
+
+            Some(HouseMut {
+                board: self,
+                house_first_x: start_column,
+                house_first_y: start_row,
+            })
+        } else {
+            None
         }
     }
 
     /// Gets an iterator of all columns in the game board
-    pub fn columns(&self) -> impl IntoIterator<Item = Column> {
-        (0..9)
-            .into_iter()
-            .map(move |index| self.column(index).unwrap())
+    pub fn columns(&self) -> impl IntoIterator<Item = Column<'_>> {
+        (0..self.size()).map(move |index| self.column(index).unwrap())
     }
 
     /// Gets an iterator of all rows in the game board
-    pub fn rows(&self) -> impl IntoIterator<Item = Row> {
-        (0..9)
-            .into_iter()
-            .map(move |index| self.row(index).unwrap())
+    pub fn rows(&self) -> impl IntoIterator<Item = Row<'_>> {
+        (0..self.size()).map(move |index| self.row(index).unwrap())
     }
 
     /// Gets an iterator for all houses in the game board
-    pub fn houses(&self) -> impl IntoIterator<Item = House> {
-        (0..3)
-            .into_iter()
-            .map(move |row| {
-                (0..3)
-                    .into_iter()
-                    .map(move |col| self.house(col, row).unwrap())
-            })
-            .flatten()
+    pub fn houses(&self) -> impl IntoIterator<Item = House<'_>> {
+        (0..self.house_height).flat_map(move |house_column| {
+            (0..self.house_width)
+                .map(move |house_row| self.house(house_row, house_column).unwrap())
+        })
+    }
+
+    /// Gets one of the two main diagonals, where 0 is the diagonal starting in the top left corner
+    /// and 1 is the diagonal starting in the top right corner. Diagonals can be retrieved for any
+    /// board, but are only a rule for boards with the diagonal variant.
+    pub fn diagonal(&self, index: usize) -> Option<Diagonal<'_>> {
+        match index {
+            0 | 1 => {
+                let size = self.size();
+                let cells = (0..size)
+                    .map(|row| {
+                        let column = if index == 0 { row } else { size - 1 - row };
+                        &self.cells[row][column]
+                    })
+                    .collect();
+
+                Some(Diagonal {
+                    cells,
+                    diagonal_n: index,
+                })
+            }
+            _ => None,
+        }
     }
 
-    /// Gets an iterator of all components within the game board
-    fn sudoku_components<'a>(
-        &'a self,
-    ) -> impl IntoIterator<Item = Box<dyn 'a + SudokuCorrectness>> {
-        let mut vec: Vec<Box<dyn SudokuCorrectness>> = vec![];
-        vec.extend(self.rows().into_iter().map(|row| {
-            let ret: Box<dyn SudokuCorrectness> = Box::new(row);
-            ret
-        }));
-        vec.extend(self.columns().into_iter().map(|row| {
-            let ret: Box<dyn SudokuCorrectness> = Box::new(row);
-            ret
-        }));
-        vec.extend(self.houses().into_iter().map(|row| {
-            let ret: Box<dyn SudokuCorrectness> = Box::new(row);
-            ret
-        }));
-        vec
-    }
-
-    /// gets the byte string equivalent of the board
+    /// Gets an iterator of the diagonals that are a rule of the game board. This is empty unless
+    /// the board has the diagonal variant.
+    pub fn diagonals(&self) -> impl IntoIterator<Item = Diagonal<'_>> {
+        let count = match self.variant {
+            GameBoardVariant::Standard => 0,
+            GameBoardVariant::Diagonal => 2,
+        };
+        (0..count).map(move |index| self.diagonal(index).unwrap())
+    }
+
+    /// Gets an iterator of every row, column, and house in the game board, followed by the
+    /// diagonals if the board is the diagonal variant
+    pub fn units(&self) -> impl Iterator<Item = Unit<'_>> {
+        let rows = self.rows().into_iter().map(Unit::Row);
+        let columns = self.columns().into_iter().map(Unit::Column);
+        let houses = self.houses().into_iter().map(Unit::House);
+        let diagonals = self.diagonals().into_iter().map(Unit::Diagonal);
+        rows.chain(columns).chain(houses).chain(diagonals)
+    }
+
+    /// gets the byte string equivalent of the board, which can be loaded with a [ByteStringLoader].
+    /// Every cell with a value is written, followed by the terminator
+    ///
+    /// [ByteStringLoader]: crate::game_creator::ByteStringLoader
     pub fn as_byte_string(&self) -> String {
         let mut buffer: Vec<u8> = Vec::new();
 
@@ -681,51 +1236,290 @@ impl GameBoard {
             }
         }
 
-        buffer.push(0b01000000);
-        buffer.push(0b01000000);
+        buffer.extend_from_slice(&BYTE_STRING_TERMINATOR);
         String::from_utf8(buffer).unwrap()
     }
 
-    /// Automatically fully notes the game board
-    pub fn auto_note(&mut self) {
-        for row in 0usize..9 {
-            for column in 0usize..9 {
-                if !self.is_valid() {
-                    return;
+    /// Gets the board as a JSON string that can be loaded with a [JSONLoader]. Only the possible
+    /// values of noted cells are kept.
+    ///
+    /// [JSONLoader]: crate::game_creator::JSONLoader
+    pub fn to_json(&self) -> String {
+        let mut entries = vec![];
+        for (row_n, row) in self.cells.iter().enumerate() {
+            for (col_n, cell) in row.iter().enumerate() {
+                entries.extend(JSONCellEntry::from_cell(col_n, row_n, cell));
+            }
+        }
+        serde_json::to_string(&entries).expect("A game board can always be serialized")
+    }
+
+    /// Saves the entire state of the board to a file, including values entered by the user and
+    /// notes.
+    ///
+    /// # Error:
+    /// This function will result in an error if an [IO error] occurs
+    ///
+    /// [IO error]: std::io::Error
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Loads a board saved with [save_to_file](GameBoard::save_to_file).
+    ///
+    /// # Error:
+    /// This function will result in an error if an [IO error] occurs, or if the file doesn't
+    /// contain a saved game board. Boards with the wrong number of rows or columns for their
+    /// houses, or with values or notes that don't fit on the board, are [InvalidData].
+    ///
+    /// [IO error]: std::io::Error
+    /// [InvalidData]: std::io::ErrorKind::InvalidData
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let board: GameBoard = serde_json::from_reader(reader)?;
+        if let Some(problem) = board.loaded_problem() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, problem));
+        }
+        Ok(board)
+    }
+
+    /// Describes why a deserialized board can't be used, if it can't
+    fn loaded_problem(&self) -> Option<String> {
+        let size = self.house_width.checked_mul(self.house_height).unwrap_or(0);
+        if size == 0 || size > MAX_SIZE {
+            return Some(format!(
+                "Invalid board size: {}x{} houses",
+                self.house_width, self.house_height
+            ));
+        }
+        if self.cells.len() != size {
+            return Some(format!("Expected {} rows, found {}", size, self.cells.len()));
+        }
+        let notes_mask = ((1u32 << size) - 1) as u16;
+        for (row_n, row) in self.cells.iter().enumerate() {
+            if row.len() != size {
+                return Some(format!(
+                    "Expected {} cells in row {}, found {}",
+                    size,
+                    row_n,
+                    row.len()
+                ));
+            }
+            for (col_n, cell) in row.iter().enumerate() {
+                let fits = match *cell {
+                    CellValue::Preset(val) | CellValue::Value(val) => {
+                        val != 0 && val as usize <= size
+                    }
+                    CellValue::Notes { maybe, deny } => (maybe | deny) & !notes_mask == 0,
+                    CellValue::Empty => true,
+                };
+                if !fits {
+                    return Some(format!(
+                        "Cell {:?} doesn't fit on the board",
+                        (col_n, row_n)
+                    ));
                 }
-                let cell_index = (column, row);
-                if let None = self.cell_value(cell_index).as_value() {
-                    let mut valid: Vec<u8> = vec![];
-                    let denies: Vec<u8> = self.cell_value(cell_index).denied_values().into_iter().flatten().collect();
-                    let maybes: Vec<u8> = self.cell_value(cell_index).maybe_values().into_iter().flatten().collect();
-                    for val in 1u8..=9 {
-                        let old = self.cells[row][column];
-                        self.cells[row][column] = CellValue::Value(val);
-                        let affected = AffectedComponents::new(self, cell_index);
-                        if affected.house().is_valid()
-                            && affected.row().is_valid()
-                            && affected.column().is_valid()
-                        {
-                            valid.push(val);
-                        }
-                        self.cells[row][column] = old;
+            }
+        }
+        None
+    }
+
+    /// Gets the board as a string where every cell is drawn as a small grid, like pencil marks on
+    /// paper. Noted cells show each of their maybe values, and cells with a value show it in the
+    /// middle. Houses are outlined with `#`.
+    pub fn pretty_with_notes(&self) -> String {
+        let (width, height) = (self.house_width, self.house_height);
+        let line_width = self.size() * (width + 1) + 1;
+        let house_line = "#".repeat(line_width);
+        let cell_line: String = (0..line_width)
+            .map(|i| {
+                if i % ((width + 1) * width) == 0 {
+                    '#'
+                } else if i % (width + 1) == 0 {
+                    '+'
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+
+        let mut output = String::new();
+        for (row_n, row) in self.cells.iter().enumerate() {
+            if row_n % height == 0 {
+                output.push_str(&house_line);
+            } else {
+                output.push_str(&cell_line);
+            }
+            output.push('\n');
+
+            for j in 0..height {
+                for (col_n, cell) in row.iter().enumerate() {
+                    output.push(if col_n % width == 0 { '#' } else { '|' });
+                    for i in 0..width {
+                        let note = (j * width + i + 1) as u8;
+                        let ch = match cell {
+                            CellValue::Notes { .. } => match cell.note_status(note) {
+                                Some(NoteStatus::Maybe) => value_char(note),
+                                _ => ' ',
+                            },
+                            _ => match cell.as_value() {
+                                Some(val) if j == height / 2 && i == width / 2 => value_char(val),
+                                _ => ' ',
+                            },
+                        };
+                        output.push(ch);
                     }
-                    //println!("Valid: {:?}", valid);
-                    valid.retain(|val| !denies.contains(val));
-                    valid.retain(|val| !maybes.contains(val));
-                    //println!("Valid after denied:  {:?}", valid);
-                    for value in valid {
-                        self.set(cell_index, &NoteMode::Maybe, value);
+                }
+                output.push_str("#\n");
+            }
+        }
+        output.push_str(&house_line);
+        output
+    }
+
+    /// Gets the board as a single line of characters, going left to right through each row from
+    /// top to bottom. Empty cells are `.`
+    pub fn as_line_string(&self) -> String {
+        self.cells
+            .iter()
+            .flatten()
+            .map(|cell| cell.as_value().map_or('.', value_char))
+            .collect()
+    }
+
+    /// Packs a standard board into one byte per cell, going left to right through each row from top
+    /// to bottom. Empty and noted cells are `0`, and filled cells are their value. The
+    /// [PRESET_BIT] is also set for preset cells. Notes aren't kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the board isn't [SIZE] by [SIZE]
+    pub fn to_bytes(&self) -> [u8; SIZE * SIZE] {
+        assert_eq!(self.size(), SIZE, "Only standard boards can be packed into bytes");
+        let mut bytes = [0; SIZE * SIZE];
+        for (byte, cell) in bytes.iter_mut().zip(self.cells.iter().flatten()) {
+            *byte = match *cell {
+                CellValue::Preset(val) => val | PRESET_BIT,
+                CellValue::Value(val) => val,
+                CellValue::Notes { .. } | CellValue::Empty => 0,
+            };
+        }
+        bytes
+    }
+
+    /// Reads a standard board from bytes created by [to_bytes](GameBoard::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BytesError> {
+        if bytes.len() != SIZE * SIZE {
+            return Err(BytesError::WrongLength(bytes.len()));
+        }
+
+        let mut board = GameBoard::new();
+        for (index, &byte) in bytes.iter().enumerate() {
+            let cell_index = (index % SIZE, index / SIZE);
+            let val = byte & !PRESET_BIT;
+            if val as usize > SIZE || (val == 0 && byte != 0) {
+                return Err(BytesError::InvalidByte(cell_index, byte));
+            }
+            board[cell_index] = match (val, byte & PRESET_BIT != 0) {
+                (0, _) => CellValue::Empty,
+                (val, true) => CellValue::Preset(val),
+                (val, false) => CellValue::Value(val),
+            };
+        }
+        Ok(board)
+    }
+
+    /// Draws the board with box-drawing characters. Houses are outlined with double lines, and
+    /// cells with single lines. Empty and noted cells are blank.
+    pub fn to_unicode(&self) -> String {
+        let size = self.size();
+        // the character where the lines between rows and columns meet, at a line index
+        let junction = |row: usize, column: usize| {
+            let double_row = row.is_multiple_of(self.house_height);
+            let double_column = column.is_multiple_of(self.house_width);
+            match (row, column) {
+                (0, 0) => '╔',
+                (0, c) if c == size => '╗',
+                (0, _) if double_column => '╦',
+                (0, _) => '╤',
+                (r, 0) if r == size => '╚',
+                (r, c) if r == size && c == size => '╝',
+                (r, _) if r == size && double_column => '╩',
+                (r, _) if r == size => '╧',
+                (_, 0) if double_row => '╠',
+                (_, 0) => '╟',
+                (_, c) if c == size && double_row => '╣',
+                (_, c) if c == size => '╢',
+                _ => match (double_column, double_row) {
+                    (true, true) => '╬',
+                    (true, false) => '╫',
+                    (false, true) => '╪',
+                    (false, false) => '┼',
+                },
+            }
+        };
+
+        let mut output = String::new();
+        for row in 0..=size {
+            let line = if row.is_multiple_of(self.house_height) { "═══" } else { "───" };
+            for column in 0..size {
+                output.push(junction(row, column));
+                output.push_str(line);
+            }
+            output.push(junction(row, size));
+            output.push('\n');
+
+            if row == size {
+                break;
+            }
+            for column in 0..size {
+                output.push(if column.is_multiple_of(self.house_width) { '║' } else { '│' });
+                output.push(' ');
+                output.push(self.cells[row][column].as_value().map_or(' ', value_char));
+                output.push(' ');
+            }
+            output.push_str("║\n");
+        }
+        output
+    }
+
+    /// Automatically fully notes the game board. Values denied in a cell are kept denied instead
+    /// of becoming maybes.
+    ///
+    /// Returns a cell that shows the board can't be completed, if there is one. When the board
+    /// isn't valid, that's one of the conflicting cells and nothing is noted. Otherwise it's the
+    /// first cell that can't be any value, and the rest of the board is still noted.
+    pub fn auto_note(&mut self) -> Result<(), CellIndex> {
+        // notes don't change whether the board is valid, so it only has to be checked once
+        if !self.is_valid() {
+            return Err(self.invalid_cells()[0]);
+        }
+        let mut contradiction = None;
+        for row in 0usize..self.size() {
+            for column in 0usize..self.size() {
+                let cell_index = (column, row);
+                let candidates = self.candidates(cell_index);
+                if candidates.is_empty() && self.cell_value(cell_index).as_value().is_none() {
+                    contradiction = contradiction.or(Some(cell_index));
+                }
+
+                let denies = self.cell_value(cell_index).denied_values().unwrap_or_default();
+                for val in candidates {
+                    if !denies.contains(&val) {
+                        self.add_maybe(cell_index, val);
                     }
                 }
             }
         }
+        contradiction.map_or(Ok(()), Err)
     }
 
     /// Clears all notes
     pub fn clear_notes(&mut self) {
-        for row in 0usize..9 {
-            for column in 0usize..9 {
+        for row in 0usize..self.size() {
+            for column in 0usize..self.size() {
                 if let CellValue::Notes { .. } = self.cell_value((column, row)) {
                     self.reset((column, row));
                 }
@@ -734,26 +1528,86 @@ impl GameBoard {
     }
 
     /// Solves the board. Returns whether the solve was successful
+    ///
+    /// If the solve wasn't successful, including when the board starts off invalid, the board is
+    /// left unchanged.
     pub fn solve(&mut self) -> bool {
-        for row in 0usize..9 {
-            for column in 0usize..9 {
-                let cell_index = (column, row);
-                if let None = self.cell_value(cell_index).as_value() {
-                    let mut viable = false;
-                    for val in 1u8..=9 {
-                        self.cells[row][column] = CellValue::Value(val);
-                        if self.is_valid() {
-                            let mut next = self.clone();
-                            if next.solve() {
-                                *self = next;
-                                viable = true;
-                                break;
-                            }
-                        }
+        self.solve_helper(0, &mut |_| {})
+    }
+
+    /// Solves the board like [solve](GameBoard::solve), pushing every value that's tried and taken
+    /// back out onto `trace` in the order it happens
+    pub fn solve_traced(&mut self, trace: &mut Vec<TraceEvent>) -> bool {
+        self.solve_helper(0, &mut |event| trace.push(event))
+    }
+
+    fn solve_helper<F: FnMut(TraceEvent)>(&mut self, depth: usize, on_event: &mut F) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+
+        match self.most_constrained_cell() {
+            Some(cell_index) => {
+                for val in self.candidates(cell_index) {
+                    let mut next = self.clone();
+                    next[cell_index] = CellValue::Value(val);
+                    on_event(TraceEvent::Try {
+                        cell: cell_index,
+                        value: val,
+                        depth,
+                    });
+                    if next.solve_helper(depth + 1, on_event) {
+                        *self = next;
+                        return true;
                     }
-                    if !viable {
+                    on_event(TraceEvent::Backtrack {
+                        cell: cell_index,
+                        value: val,
+                        depth,
+                    });
+                }
+                false
+            }
+            None => self.is_complete(),
+        }
+    }
+
+    /// Solves the board without recursing or cloning the board. Cells are filled in place, and
+    /// a stack of the filled cells and the values left to try for each is used to backtrack.
+    ///
+    /// Returns whether the solve was successful. If it wasn't, the board is left unchanged.
+    pub fn solve_iterative(&mut self) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+
+        let original = self.cells.clone();
+        let mut stack: Vec<(CellIndex, Vec<u8>)> = vec![];
+
+        while let Some(cell_index) = self.most_constrained_cell() {
+            let mut candidates = self.candidates(cell_index);
+            // values are popped off the end, so reverse them to try the smallest first
+            candidates.reverse();
+            stack.push((cell_index, candidates));
+
+            // place the next value, undoing cells that have no values left to try
+            loop {
+                let (cell_index, next_val) = match stack.last_mut() {
+                    Some((cell_index, candidates)) => (*cell_index, candidates.pop()),
+                    None => {
+                        self.cells = original;
                         return false;
                     }
+                };
+                match next_val {
+                    Some(val) => {
+                        self[cell_index] = CellValue::Value(val);
+                        break;
+                    }
+                    None => {
+                        self[cell_index] = original[cell_index.1][cell_index.0];
+                        stack.pop();
+                    }
                 }
             }
         }
@@ -761,12 +1615,67 @@ impl GameBoard {
         self.is_valid() && self.is_complete()
     }
 
+    /// The unset cell with the fewest candidates, which is the best cell to guess the value of
+    /// while solving. Returns `None` if every cell is set
+    pub fn most_constrained_cell(&self) -> Option<CellIndex> {
+        let mut best: Option<(CellIndex, usize)> = None;
+        for cell_index in self.iter_unset() {
+            let count = self.candidates(cell_index).len();
+            if best.is_none_or(|(_, best_count)| count < best_count) {
+                best = Some((cell_index, count));
+                if count <= 1 {
+                    break;
+                }
+            }
+        }
+        best.map(|(cell_index, _)| cell_index)
+    }
+
     /// Returns a solutions tree for the given board that can timeout if it detects the solutions space
     /// is too big
-    pub fn solutions(&self) -> Option<SolutionsTree> {
+    pub fn solutions(&self) -> SolveOutcome {
         SolutionsTree::solve(self)
     }
 
+    /// The difficulty of the board, if it can be solved using known techniques. Returns `None` if
+    /// solving the board requires guessing
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        Solver::default()
+            .solve(self)
+            .ok()
+            .map(|solution| solution.difficulty)
+    }
+
+    /// A quick estimate of how hard the board is, from the [Entropy] of the board with every
+    /// possible value noted. Higher is harder. Much faster than
+    /// [difficulty](GameBoard::difficulty), but doesn't take the techniques needed to solve the
+    /// board into account
+    ///
+    /// [Entropy]: crate::advanced_solver::entropy::Entropy
+    pub fn entropy_difficulty(&self) -> u64 {
+        let mut noted = self.clone();
+        noted.clear_notes();
+        let _ = noted.auto_note();
+        Entropy::new(&noted).value()
+    }
+
+    /// Fills in every cell that can be found using only naked and hidden singles, without
+    /// guessing. Cells that are left empty are noted with their possible values
+    pub fn logical_fill(&self) -> GameBoard {
+        let mut board = self.clone();
+        board.set_auto_remove(true);
+        board.clear_notes();
+        let _ = board.auto_note();
+        while let Ok(next) = NakedSingle
+            .apply_to(&board)
+            .or_else(|_| HiddenSingle.apply_to(&board))
+        {
+            board = next;
+        }
+        board.set_auto_remove(self.auto_remove);
+        board
+    }
+
     /// Returns a solutions tree for the given board
     pub fn force_solutions(&self) -> Option<SolutionsTree> {
         SolutionsTree::force_solve(self)
@@ -777,17 +1686,89 @@ impl GameBoard {
         SolutionsTree::try_solve(self, cell_index, val)
     }
 
+    /// The number of preset cells
+    pub fn count_givens(&self) -> usize {
+        self.iter_presets().len()
+    }
+
+    /// Checks that the givens of the board make a proper puzzle: no value is repeated in a unit,
+    /// standard boards have at least [MIN_GIVENS] givens, and there's exactly one solution. Values
+    /// and notes entered by the player are ignored.
+    ///
+    /// # Error:
+    /// Returns the first [PuzzleProblem] found. Repeated values are checked first, then the
+    /// number of givens, and then the number of solutions
+    pub fn validate_puzzle(&self) -> Result<(), PuzzleProblem> {
+        let givens = self.givens_only();
+        if !givens.is_valid() {
+            return Err(PuzzleProblem::Invalid);
+        }
+        if self.size() == SIZE && self.count_givens() < MIN_GIVENS {
+            return Err(PuzzleProblem::TooFewGivens);
+        }
+        match count_solutions(&givens, 2) {
+            0 => Err(PuzzleProblem::NoSolution),
+            1 => Ok(()),
+            _ => Err(PuzzleProblem::MultipleSolutions),
+        }
+    }
+
+    /// Whether the board is a minimal puzzle, where removing any preset cell would give the board
+    /// more than one solution. The board should have a unique solution
+    pub fn is_minimal(&self) -> bool {
+        self.iter_presets()
+            .into_iter()
+            .all(|cell_index| !self.is_redundant_preset(cell_index))
+    }
+
+    /// Creates a minimal puzzle from the board by removing preset cells, one at a time, as long as
+    /// the board still has a unique solution. The board should have a unique solution
+    pub fn minimize(&self) -> GameBoard {
+        let mut minimized = self.clone();
+        for cell_index in self.iter_presets() {
+            if minimized.is_redundant_preset(cell_index) {
+                minimized[cell_index] = CellValue::Empty;
+            }
+        }
+        minimized
+    }
+
+    /// The indices of every preset cell
+    fn iter_presets(&self) -> Vec<CellIndex> {
+        (0..self.size())
+            .flat_map(|row| (0..self.size()).map(move |col| (col, row)))
+            .filter(|&cell_index| matches!(self[cell_index], CellValue::Preset(_)))
+            .collect()
+    }
+
+    /// Whether a preset cell can be removed without the board having another solution. As the
+    /// board is assumed to only have one solution, any other solution must have a different value
+    /// in the removed cell
+    fn is_redundant_preset(&self, cell_index: CellIndex) -> bool {
+        let value = match self[cell_index] {
+            CellValue::Preset(value) => value,
+            _ => return false,
+        };
+
+        let mut removed = self.clone();
+        removed[cell_index] = CellValue::Empty;
+        removed.candidates(cell_index).into_iter().all(|other| {
+            if other == value {
+                return true;
+            }
+            let mut next = removed.clone();
+            next[cell_index] = CellValue::Value(other);
+            !next.solve()
+        })
+    }
+
     pub(crate) fn swap_rows(&mut self, row1: usize, row2: usize) {
-        let temp = self.cells[row1];
-        self.cells[row1] = self.cells[row2];
-        self.cells[row2] = temp;
+        self.cells.swap(row1, row2);
     }
 
     pub(crate) fn swap_columns(&mut self, col1: usize, col2: usize) {
-        for row in 0usize..9 {
-            let temp = self[row][col1];
-            self[row][col1] = self[row][col2];
-            self[row][col2] = temp;
+        for row in 0usize..self.size() {
+            self[row].swap(col1, col2);
         }
     }
 
@@ -796,10 +1777,9 @@ impl GameBoard {
     ///
     /// [CellIndex]: crate::game_board::CellIndex
     pub fn iter_unset(&self) -> impl IntoIterator<Item = CellIndex> {
-        let vector: Vec<_> = (0usize..9)
-            .into_iter()
+        let vector: Vec<_> = (0usize..self.size())
             .flat_map(move |row| {
-                (0usize..9).into_iter().filter_map(move |col| {
+                (0usize..self.size()).filter_map(move |col| {
                     let index = (col, row);
                     match &self[index] {
                         CellValue::Preset(_) => None,
@@ -813,6 +1793,84 @@ impl GameBoard {
         vector
     }
 
+    /// The number of cells that don't have a value, including cells with notes
+    pub fn empty_count(&self) -> usize {
+        self.cells
+            .iter()
+            .flatten()
+            .filter(|cell| cell.as_value().is_none())
+            .count()
+    }
+
+    /// The fraction of cells that have a value, from 0.0 to 1.0
+    pub fn progress(&self) -> f64 {
+        let total = self.size() * self.size();
+        (total - self.empty_count()) as f64 / total as f64
+    }
+
+    /// Compares every cell that isn't preset to its value in the solution
+    pub fn grade(&self, solution: &GameBoard) -> Grade {
+        let mut grade = Grade::default();
+        for row in 0..self.size() {
+            for col in 0..self.size() {
+                let index = (col, row);
+                match self[index] {
+                    CellValue::Preset(_) => {}
+                    CellValue::Value(val) if solution[index].as_value() == Some(val) => {
+                        grade.correct.push(index)
+                    }
+                    CellValue::Value(_) => grade.incorrect.push(index),
+                    CellValue::Notes { .. } | CellValue::Empty => grade.empty.push(index),
+                }
+            }
+        }
+        grade
+    }
+
+    /// Whether every preset cell of this board has the same value in the solution
+    pub fn respects_givens(&self, solution: &GameBoard) -> bool {
+        if self.size() != solution.size() {
+            return false;
+        }
+
+        self.cells
+            .iter()
+            .flatten()
+            .zip(solution.cells.iter().flatten())
+            .all(|(cell, solved)| match cell {
+                CellValue::Preset(val) => solved.as_value() == Some(*val),
+                _ => true,
+            })
+    }
+
+    /// Whether a board is a complete and valid solution to this puzzle, keeping every preset cell
+    pub fn is_solved_by(&self, candidate: &GameBoard) -> bool {
+        candidate.is_victory() && self.respects_givens(candidate)
+    }
+
+    /// A copy of the board with only the preset cells, and every other cell empty
+    pub fn givens_only(&self) -> GameBoard {
+        let mut givens = self.clone();
+        givens.clear_user_values();
+        givens
+    }
+
+    /// Copies the values and notes from another board into this one. Preset cells of either board
+    /// are skipped, so the givens of this board are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the boards aren't the same size
+    pub fn apply_progress(&mut self, other: &GameBoard) {
+        assert_eq!(self.size(), other.size(), "Progress must come from a board of the same size");
+        for (cell, progress) in self.cells.iter_mut().flatten().zip(other.cells.iter().flatten()) {
+            let is_progress = matches!(progress, CellValue::Value(_) | CellValue::Notes { .. });
+            if is_progress && !matches!(cell, CellValue::Preset(_)) {
+                *cell = *progress;
+            }
+        }
+    }
+
     /// Checks if the boards is completely filled and valid
     #[inline]
     pub fn is_victory(&self) -> bool {
@@ -821,10 +1879,46 @@ impl GameBoard {
 }
 
 impl SudokuCorrectness for GameBoard {
+    /// Checks every unit in one pass over the board, keeping a bitmask of the values used in each
     fn is_valid(&self) -> bool {
-        for component in self.sudoku_components() {
-            if !component.is_valid() {
-                return false;
+        let size = self.size();
+        let mut rows = [0u32; MAX_SIZE];
+        let mut columns = [0u32; MAX_SIZE];
+        let mut houses = [0u32; MAX_SIZE];
+        let mut diagonals = [0u32; 2];
+        let diagonal = self.variant == GameBoardVariant::Diagonal;
+
+        for (row, cells) in self.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                let val = match cell.as_value() {
+                    Some(val) => val,
+                    None => continue,
+                };
+                let bit = 1 << (val - 1);
+                let house = row / self.house_height * self.house_height + column / self.house_width;
+                if rows[row] & bit != 0
+                    || columns[column] & bit != 0
+                    || houses[house] & bit != 0
+                {
+                    return false;
+                }
+                rows[row] |= bit;
+                columns[column] |= bit;
+                houses[house] |= bit;
+
+                if diagonal {
+                    for (n, on_diagonal) in [row == column, row + column == size - 1]
+                        .iter()
+                        .enumerate()
+                    {
+                        if *on_diagonal {
+                            if diagonals[n] & bit != 0 {
+                                return false;
+                            }
+                            diagonals[n] |= bit;
+                        }
+                    }
+                }
             }
         }
         true
@@ -832,38 +1926,33 @@ impl SudokuCorrectness for GameBoard {
 
     fn invalid_cells(&self) -> Vec<CellIndex> {
         let set: HashSet<_> = self
-            .sudoku_components()
-            .into_iter()
-            .map(|comp| comp.invalid_cells())
-            .flatten()
+            .units()
+            .flat_map(|comp| comp.invalid_cells())
             .collect();
 
         Vec::from_iter(set)
     }
 
     fn is_complete(&self) -> bool {
-        for component in self.sudoku_components() {
-            if !component.is_complete() {
-                return false;
-            }
-        }
-        true
+        let filled = self
+            .cells
+            .iter()
+            .flatten()
+            .all(|cell| cell.as_value().is_some());
+        filled && self.is_valid()
     }
 
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
-        let mut ret = vec![];
-        for j in 0..9 {
-            for i in 0..9 {
-                let cell = &self[j][i];
-                ret.push(((i, j), cell))
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue)) {
+        for (j, row) in self.cells.iter().enumerate() {
+            for (i, cell) in row.iter().enumerate() {
+                visitor((i, j), cell);
             }
         }
-        ret
     }
 }
 
 impl Index<usize> for GameBoard {
-    type Output = [CellValue; 9];
+    type Output = [CellValue];
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.cells[index]
@@ -910,33 +1999,93 @@ impl<'a> IntoIterator for &'a GameBoard {
 impl Debug for GameBoard {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         if f.alternate() {
-            writeln!(f, "+{}+", "-".repeat(17))?;
+            let width = self.size() * 2 - 1;
+            writeln!(f, "+{}+", "-".repeat(width))?;
             for (index, row) in self.rows().into_iter().enumerate() {
-                if index > 0 && index % 3 == 0 {
-                    writeln!(f, "+{}+", "-".repeat(17))?;
+                if index > 0 && index % self.house_height == 0 {
+                    writeln!(f, "+{}+", "-".repeat(width))?;
                 }
-                let mut to_add = HashMap::new();
-                let vector: Vec<String> =
-                row.indices_and_cells()
+                let vector: Vec<String> = row
+                    .indices_and_cells()
                     .into_iter()
-                    .map(|(index, val)| {
-                        if let CellValue::Notes { status } = val {
-                            to_add.insert(index, status);
-                        }
-                        val.as_value().map(|v| format!("{}", v)).unwrap_or(" ".to_string())
-                    })
+                    .map(|(_, val)| val.as_value().map(|v| format!("{}", v)).unwrap_or(" ".to_string()))
                     .collect();
 
-                writeln!(f, "|{}|{}|{}|", vector[0..3].join(" "), vector[3..6].join(" "), vector[6..9].join(" "))?;
+                let houses: Vec<String> = vector
+                    .chunks(self.house_width)
+                    .map(|chunk| chunk.join(" "))
+                    .collect();
+                writeln!(f, "|{}|", houses.join("|"))?;
 
             }
-            writeln!(f, "+{}+", "-".repeat(17))
+            writeln!(f, "+{}+", "-".repeat(width))
         } else {
             write!(f, "{:?}", self.cells)
         }
     }
 }
 
+impl Display for GameBoard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let separator = vec!["-".repeat(self.house_width * 2 + 1); self.house_height].join("+");
+        for (index, row) in self.cells.iter().enumerate() {
+            if index % self.house_height == 0 {
+                writeln!(f, "+{}+", separator)?;
+            }
+            let houses: Vec<String> = row
+                .chunks(self.house_width)
+                .map(|house| {
+                    house
+                        .iter()
+                        .map(|cell| cell.as_value().map_or('.', value_char).to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect();
+            writeln!(f, "| {} |", houses.join(" | "))?;
+        }
+        write!(f, "+{}+", separator)
+    }
+}
+
+/// Names a cell the way players do, as its row and column counted from 1. Cell `(4, 2)` is
+/// `"R3C5"`
+pub fn cell_index_to_rc(index: CellIndex) -> String {
+    let (column, row) = index;
+    format!("R{}C{}", row + 1, column + 1)
+}
+
+/// Reads a cell named by its row and column counted from 1, such as `"R3C5"`. Case is ignored.
+/// Returns `None` if the name isn't in that form, or the row or column is 0
+pub fn rc_to_cell_index(rc: &str) -> Option<CellIndex> {
+    // only plain digits, as parse also accepts a leading +
+    let number = |digits: &str| -> Option<usize> {
+        if digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse().ok()
+        } else {
+            None
+        }
+    };
+
+    let rc = rc.trim().to_ascii_uppercase();
+    let rest = rc.strip_prefix('R')?;
+    let (row, column) = rest.split_at(rest.find('C')?);
+    let row = number(row)?;
+    let column = number(&column[1..])?;
+    if row == 0 || column == 0 {
+        return None;
+    }
+    Some((column - 1, row - 1))
+}
+
+/// The character used to show a value. Values past 9 are shown as letters
+pub(crate) fn value_char(val: u8) -> char {
+    match val {
+        1..=9 => (b'0' + val) as char,
+        _ => (b'A' + (val - 10)) as char,
+    }
+}
+
 /// A convenience struct to get the row, column, and house "seen" by a cell at a given index
 pub struct AffectedComponentsMut<'a> {
     index: CellIndex,
@@ -961,8 +2110,9 @@ impl<'a> AffectedComponentsMut<'a> {
 
     /// The affected house
     pub fn house(self) -> HouseMut<'a> {
+        let (house_width, house_height) = (self.board.house_width, self.board.house_height);
         self.board
-            .house_mut(self.index.1 / 3, self.index.0 / 3)
+            .house_mut(self.index.1 / house_height, self.index.0 / house_width)
             .unwrap()
     }
 }
@@ -992,12 +2142,306 @@ impl<'a> AffectedComponents<'a> {
     /// The affected house
     pub fn house(&self) -> House<'a> {
         self.board
-            .house(self.index.1 / 3, self.index.0 / 3)
+            .house(self.index.1 / self.board.house_height, self.index.0 / self.board.house_width)
             .unwrap()
     }
 
+    /// The affected diagonals. Only boards with the diagonal variant have affected diagonals
+    pub fn diagonals(&self) -> Vec<Diagonal<'a>> {
+        let (column, row) = self.index;
+        let size = self.board.size();
+        self.board
+            .diagonals()
+            .into_iter()
+            .filter(|diagonal| {
+                if diagonal.diagonal_n == 0 {
+                    column == row
+                } else {
+                    column == size - 1 - row
+                }
+            })
+            .collect()
+    }
+
     /// Checks whether all the components are valid
     pub fn is_valid(&self) -> bool {
-        self.row().is_valid() && self.column().is_valid() && self.house().is_valid()
+        self.row().is_valid()
+            && self.column().is_valid()
+            && self.house().is_valid()
+            && self.diagonals().iter().all(|diagonal| diagonal.is_valid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The indices of every cell in the house that contains a cell
+    fn house_indices(board: &GameBoard, index: CellIndex) -> Vec<CellIndex> {
+        AffectedComponents::new(board, index)
+            .house()
+            .indices_and_cells()
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The indices of every cell in the mutable house that contains a cell
+    fn house_mut_indices(board: &mut GameBoard, index: CellIndex) -> Vec<CellIndex> {
+        AffectedComponentsMut::new(board, index)
+            .house()
+            .indices_and_cells()
+            .into_iter()
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Checks that every cell is in its own house, and that the house has the right shape
+    fn assert_houses_contain_their_cells(mut board: GameBoard) {
+        let (house_width, house_height) = (board.house_width(), board.house_height());
+        for row in 0..board.size() {
+            for column in 0..board.size() {
+                let index = (column, row);
+                for house in [house_indices(&board, index), house_mut_indices(&mut board, index)] {
+                    assert!(house.contains(&index), "{:?} isn't in its house", index);
+                    assert_eq!(house.len(), board.size());
+                    assert!(house.iter().all(|&(other_column, other_row)| {
+                        other_column / house_width == column / house_width
+                            && other_row / house_height == row / house_height
+                    }));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn house_contains_top_right_cell() {
+        let board = GameBoard::new();
+        assert!(house_indices(&board, (8, 0)).contains(&(8, 0)));
+    }
+
+    #[test]
+    fn square_houses_contain_their_cells() {
+        assert_houses_contain_their_cells(GameBoard::new());
+    }
+
+    #[test]
+    fn rectangular_houses_contain_their_cells() {
+        assert_houses_contain_their_cells(GameBoard::with_house_size(3, 2));
+        assert_houses_contain_their_cells(GameBoard::with_house_size(2, 3));
+    }
+
+    /// A path in the temporary directory for a test to save a board to
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sudoku-test-{}-{}.json", std::process::id(), name))
+    }
+
+    /// Writes the json to a file and loads it as a saved board
+    fn load_json(name: &str, json: &str) -> std::io::Result<GameBoard> {
+        let path = temp_path(name);
+        std::fs::write(&path, json)?;
+        let loaded = GameBoard::load_from_file(&path);
+        let _ = std::fs::remove_file(&path);
+        loaded
+    }
+
+    #[test]
+    fn save_and_load_keeps_notes_and_values() {
+        let mut board = GameBoard::new();
+        board[(0, 0)] = CellValue::Preset(3);
+        board.set((1, 0), &NoteMode::Value, 4);
+        board.set((2, 0), &NoteMode::Maybe, 7);
+        board.set((2, 0), &NoteMode::Maybe, 9);
+        board.set((2, 0), &NoteMode::Deny, 1);
+
+        let path = temp_path("round-trip");
+        board.save_to_file(&path).unwrap();
+        let loaded = GameBoard::load_from_file(&path);
+        let _ = std::fs::remove_file(&path);
+        let loaded = loaded.unwrap();
+
+        assert_eq!(loaded, board);
+        assert_eq!(loaded.cell_value((1, 0)), &CellValue::Value(4));
+        assert_eq!(loaded.cell_value((2, 0)).maybe_values(), Some(vec![7, 9]));
+        assert_eq!(loaded.cell_value((2, 0)).note_status(1), Some(NoteStatus::Deny));
+    }
+
+    #[test]
+    fn load_rejects_malformed_boards() {
+        let mut json = serde_json::to_value(GameBoard::new()).unwrap();
+        let cells = json["cells"].clone();
+
+        let mut ragged = json.clone();
+        ragged["cells"][4].as_array_mut().unwrap().pop();
+        let mut short = json.clone();
+        short["cells"].as_array_mut().unwrap().pop();
+        let mut wrong_houses = json.clone();
+        wrong_houses["house_width"] = 2.into();
+        let mut empty_houses = json.clone();
+        empty_houses["house_width"] = 0.into();
+        empty_houses["cells"] = serde_json::Value::Array(vec![]);
+
+        json["cells"][0][0] = serde_json::to_value(CellValue::Value(10)).unwrap();
+        let big_value = json.clone();
+        json["cells"][0][0] = serde_json::to_value(CellValue::Preset(0)).unwrap();
+        let zero_value = json.clone();
+        json["cells"][0][0] = serde_json::to_value(CellValue::Notes {
+            maybe: 1 << 9,
+            deny: 0,
+        })
+        .unwrap();
+        let big_note = json.clone();
+        json["cells"] = cells;
+        assert!(load_json("valid", &json.to_string()).is_ok());
+
+        for (name, malformed) in [
+            ("ragged", ragged),
+            ("short", short),
+            ("wrong-houses", wrong_houses),
+            ("empty-houses", empty_houses),
+            ("big-value", big_value),
+            ("zero-value", zero_value),
+            ("big-note", big_note),
+        ] {
+            let error = load_json(name, &malformed.to_string()).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData, "{}", name);
+        }
+    }
+
+    #[test]
+    fn diagonal_duplicate_is_invalid() {
+        for (a, b) in [((0, 0), (8, 8)), ((8, 0), (0, 8)), ((2, 6), (6, 2))] {
+            let mut board = GameBoard::new();
+            board[a] = CellValue::Preset(4);
+            board[b] = CellValue::Preset(4);
+            assert!(board.is_valid(), "{:?} and {:?} only share a diagonal", a, b);
+
+            let diagonal = board.with_variant(GameBoardVariant::Diagonal);
+            assert!(!diagonal.is_valid(), "{:?} and {:?} share a diagonal", a, b);
+            assert!(diagonal.invalid_cells().contains(&a));
+            assert!(diagonal.invalid_cells().contains(&b));
+        }
+    }
+
+    #[test]
+    fn diagonal_candidates_exclude_diagonal_values() {
+        let mut board = GameBoard::new().with_variant(GameBoardVariant::Diagonal);
+        board[(0, 0)] = CellValue::Preset(4);
+        board[(8, 0)] = CellValue::Preset(5);
+        assert!(!board.candidates((4, 4)).contains(&4));
+        assert!(!board.candidates((4, 4)).contains(&5));
+        assert!(board.candidates((4, 5)).contains(&4));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        use crate::game_creator::{GameCreator, JSONLoader};
+
+        let mut board = GameBoard::new();
+        board[(0, 0)] = CellValue::Preset(1);
+        board[(8, 8)] = CellValue::Preset(9);
+        board.set((4, 2), &NoteMode::Value, 6);
+        board.set((5, 7), &NoteMode::Maybe, 2);
+        board.set((5, 7), &NoteMode::Maybe, 3);
+
+        let loaded = JSONLoader::from_string(board.to_json()).into_game().unwrap();
+        assert_eq!(loaded, board);
+    }
+
+    #[test]
+    fn json_keeps_only_maybes() {
+        use crate::game_creator::{GameCreator, JSONLoader};
+
+        let mut board = GameBoard::new();
+        board.set((3, 3), &NoteMode::Maybe, 2);
+        board.set((3, 3), &NoteMode::Deny, 5);
+        board.set((6, 1), &NoteMode::Deny, 7);
+
+        let loaded = JSONLoader::from_string(board.to_json()).into_game().unwrap();
+        assert_eq!(loaded.cell_value((3, 3)).maybe_values(), Some(vec![2]));
+        assert_eq!(loaded.cell_value((3, 3)).note_status(5), None);
+        assert_eq!(loaded.cell_value((6, 1)), &CellValue::Empty);
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut board = GameBoard::with_house_size(3, 2).with_variant(GameBoardVariant::Diagonal);
+        board[(1, 1)] = CellValue::Preset(2);
+        board.set((4, 3), &NoteMode::Deny, 1);
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(serde_json::from_str::<GameBoard>(&json).unwrap(), board);
+    }
+
+    #[test]
+    fn every_cell_has_twenty_peers() {
+        let board = GameBoard::new();
+        for row in 0..9 {
+            for column in 0..9 {
+                let peers = board.peers((column, row));
+                assert_eq!(peers.len(), 20);
+                assert!(!peers.contains(&(column, row)));
+            }
+        }
+    }
+
+    #[test]
+    fn peers_of_a_cell() {
+        let peers = GameBoard::new().peers((4, 4));
+        assert!(peers.contains(&(4, 0)));
+        assert!(peers.contains(&(0, 4)));
+        assert!(peers.contains(&(3, 5)));
+        assert!(!peers.contains(&(2, 2)));
+        assert!(!peers.contains(&(6, 6)));
+    }
+
+    #[test]
+    fn sees_cells_in_the_same_unit() {
+        let board = GameBoard::new();
+        // same row
+        assert!(board.sees((0, 3), (8, 3)));
+        // same column
+        assert!(board.sees((5, 0), (5, 8)));
+        // same house, but not the same row or column
+        assert!(board.sees((3, 3), (5, 5)));
+        // unrelated
+        assert!(!board.sees((0, 0), (4, 4)));
+        assert!(!board.sees((2, 0), (3, 1)));
+        // a cell doesn't see itself
+        assert!(!board.sees((4, 4), (4, 4)));
+    }
+
+    #[test]
+    fn sees_matches_peers() {
+        let board = GameBoard::with_house_size(3, 2);
+        for a in board.iter_unset() {
+            let peers = board.peers(a);
+            for b in board.iter_unset() {
+                assert_eq!(board.sees(a, b), peers.contains(&b), "{:?} {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn reset_gives_peers_the_value_back() {
+        let mut board = GameBoard::new();
+        for &index in &[(8, 0), (0, 8), (1, 1), (2, 0), (3, 0), (7, 7)] {
+            board.add_maybe(index, 5);
+        }
+        // (3, 0) still can't be 5 once (0, 0) is reset
+        board.set((3, 5), &NoteMode::Value, 5);
+
+        board.set((0, 0), &NoteMode::Value, 5);
+        for &index in &[(8, 0), (0, 8), (1, 1), (2, 0), (3, 0)] {
+            assert_eq!(board.cell_value(index).note_status(5), None);
+        }
+        board.add_deny((2, 0), 5);
+
+        board.reset((0, 0));
+        for &index in &[(8, 0), (0, 8), (1, 1)] {
+            assert_eq!(board.cell_value(index).note_status(5), Some(NoteStatus::Maybe));
+        }
+        assert_eq!(board.cell_value((2, 0)).note_status(5), Some(NoteStatus::Deny));
+        assert_eq!(board.cell_value((3, 0)).note_status(5), None);
+        assert_eq!(board.cell_value((7, 7)).note_status(5), Some(NoteStatus::Maybe));
     }
 }
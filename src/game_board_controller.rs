@@ -2,9 +2,13 @@
 
 use piston::input::GenericEvent;
 
+use crate::advanced_solver::techniques::{NakedSingle, Technique};
 use crate::advanced_solver::Solver;
-use crate::GameBoard;
-use std::time::Duration;
+use crate::ui::Button;
+use crate::validity::SudokuCorrectness;
+use crate::{cell_index_to_rc, CellIndex, GameBoard, GameSettings};
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 /// Handles events for the game board
 pub struct GameBoardController {
@@ -12,11 +16,26 @@ pub struct GameBoardController {
     pub game_board: GameBoard,
     /// Selected cell
     pub selected_cell: Option<(usize, usize)>,
+    /// Cells selected together by shift-clicking. Maybe and deny notes are set in all of them
+    pub selected_cells: HashSet<CellIndex>,
     cursor_pos: [f64; 2],
+    shift_held: bool,
     /// Note mode
     pub note_mode: NoteMode,
     /// Set if a number should be highlighted
     pub maybe_highlighted_number: Option<u8>,
+    /// On-screen buttons that can be clicked
+    pub buttons: Vec<Button>,
+    /// The number of values entered that conflicted with another cell
+    pub mistakes: u32,
+    /// Settings that change how values and notes are entered
+    pub game_settings: GameSettings,
+    /// Called with the cell when a value entered in it conflicts with another cell
+    pub on_conflict: Option<Box<dyn FnMut(CellIndex)>>,
+    /// When the timer was last started. `None` while the timer is paused
+    timer_start: Option<Instant>,
+    /// The time played before the timer was last started
+    time_played: Duration,
 }
 
 /// The method that the controller inputs numbers in the game board
@@ -32,38 +51,257 @@ pub enum NoteMode {
 impl GameBoardController {
     /// Creates a new game board controller
     pub fn new(game_board: GameBoard) -> Self {
-        GameBoardController {
+        let mut controller = GameBoardController {
             game_board,
             selected_cell: None,
+            selected_cells: HashSet::new(),
             cursor_pos: [0.0; 2],
+            shift_held: false,
             note_mode: NoteMode::Value,
             maybe_highlighted_number: None,
+            buttons: default_buttons(),
+            mistakes: 0,
+            game_settings: GameSettings::new(),
+            on_conflict: None,
+            timer_start: Some(Instant::now()),
+            time_played: Duration::from_secs(0),
+        };
+        controller.layout_buttons([10.0; 2], 400.0);
+        controller
+    }
+
+    /// Sets the game settings. The board is auto noted if the settings say to
+    pub fn with_settings(mut self, game_settings: GameSettings) -> Self {
+        self.game_settings = game_settings;
+        self.start_game();
+        self
+    }
+
+    /// Sets up the board at the beginning of a game
+    fn start_game(&mut self) {
+        if self.game_settings.auto_note {
+            let _ = self.game_board.auto_note();
+        }
+    }
+
+    /// Replaces the board with a new game. The selection, highlighted number, mistakes, and timer
+    /// are reset, and the note mode goes back to setting values
+    pub fn load(&mut self, board: GameBoard) {
+        self.game_board = board;
+        self.selected_cell = None;
+        self.selected_cells.clear();
+        self.maybe_highlighted_number = None;
+        self.note_mode = NoteMode::Value;
+        self.mistakes = 0;
+        self.restart_timer();
+        self.start_game();
+    }
+
+    /// Starts the timer again from zero, unpausing it
+    fn restart_timer(&mut self) {
+        self.timer_start = Some(Instant::now());
+        self.time_played = Duration::from_secs(0);
+    }
+
+    /// Sets the function called when a value entered in a cell conflicts with another cell
+    pub fn set_on_conflict<F: FnMut(CellIndex) + 'static>(&mut self, on_conflict: F) {
+        self.on_conflict = Some(Box::new(on_conflict));
+    }
+
+    /// Lines the buttons up in a row below a board at the given position and size
+    pub fn layout_buttons(&mut self, position: [f64; 2], size: f64) {
+        let gap = 10.0;
+        let count = self.buttons.len() as f64;
+        let width = (size - gap * (count - 1.0)) / count;
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            button.rect = [
+                position[0] + i as f64 * (width + gap),
+                position[1] + size + 55.0,
+                width,
+                35.0,
+            ];
+        }
+    }
+
+    /// The total time the game has been played, not counting time while paused
+    pub fn elapsed(&self) -> Duration {
+        match self.timer_start {
+            Some(start) => self.time_played + start.elapsed(),
+            None => self.time_played,
+        }
+    }
+
+    /// Whether the timer is paused
+    pub fn is_paused(&self) -> bool {
+        self.timer_start.is_none()
+    }
+
+    /// Pauses the game if it's running, or resumes it if it's paused. While paused, the timer is
+    /// stopped and every [event](GameBoardController::event) other than resuming is ignored, so
+    /// the board can't be changed without the time counting
+    pub fn toggle_pause(&mut self) {
+        match self.timer_start.take() {
+            Some(start) => self.time_played += start.elapsed(),
+            None => self.timer_start = Some(Instant::now()),
+        }
+    }
+
+    /// Selects a cell that can be solved with the known techniques and highlights its value, without
+    /// placing it. Returns whether a hint was found
+    fn show_hint(&mut self) -> bool {
+        let solver = Solver::new(Duration::from_secs(2));
+        match solver.hint(&self.game_board) {
+            Some(hint) => {
+                println!(
+                    "Hint for {} found using: {:?}",
+                    cell_index_to_rc(hint.cell),
+                    hint.techniques
+                );
+                self.selected_cell = Some(hint.cell);
+                self.maybe_highlighted_number = Some(hint.value);
+                true
+            }
+            None => {
+                println!("Could not find a hint using known techniques");
+                false
+            }
+        }
+    }
+
+    /// Sets a cell using the current note mode, counting a mistake if a value creates a new
+    /// conflict. Notes are set in every selected cell, except for illegal maybes if only legal
+    /// notes are allowed.
+    fn set(&mut self, ind: CellIndex, val: u8) {
+        if let NoteMode::Value = self.note_mode {
+            let before = self.game_board.invalid_cells();
+            self.game_board.set_auto_remove(self.game_settings.auto_remove);
+            self.game_board.set(ind, &self.note_mode, val);
+            let after = self.game_board.invalid_cells();
+            if after.contains(&ind) {
+                if let Some(on_conflict) = &mut self.on_conflict {
+                    on_conflict(ind);
+                }
+            }
+            if after.iter().any(|cell| !before.contains(cell)) {
+                self.mistakes += 1;
+            } else if self.game_settings.auto_fill {
+                self.auto_fill();
+            }
+        } else if self.selected_cells.is_empty() {
+            self.set_note(ind, val);
+        } else {
+            for cell in self.selected_cells.clone() {
+                self.set_note(cell, val);
+            }
+        }
+    }
+
+    /// Sets a note in a cell using the current note mode. Adding a maybe of a value that's already
+    /// in the same row, column, or house is refused if only legal notes are allowed
+    fn set_note(&mut self, ind: CellIndex, val: u8) {
+        let adds_maybe = matches!(self.note_mode, NoteMode::Maybe)
+            && !self.game_board.cell_value(ind).is_or_maybe(val);
+        if adds_maybe
+            && self.game_settings.legal_notes_only
+            && self.game_board.would_conflict(ind, val)
+        {
+            println!("{} can't be {}", cell_index_to_rc(ind), val);
+            return;
+        }
+        self.game_board.set(ind, &self.note_mode, val);
+    }
+
+    /// Fills in cells that only have one possible value, until there are none left
+    fn auto_fill(&mut self) {
+        loop {
+            let mut noted = self.game_board.clone();
+            noted.clear_notes();
+            if noted.auto_note().is_err() {
+                return;
+            }
+            let next = match NakedSingle.apply_to(&noted) {
+                Ok(next) => next,
+                Err(()) => return,
+            };
+
+            let filled = self.game_board.iter_unset().into_iter().find_map(|cell| {
+                next.cell_value(cell).as_value().map(|val| (cell, val))
+            });
+            match filled {
+                Some((cell, val)) => {
+                    let mut board = self.game_board.clone();
+                    board.set(cell, &NoteMode::Value, val);
+                    if !board.is_valid() {
+                        return;
+                    }
+                    self.game_board = board;
+                }
+                None => return,
+            }
         }
     }
 
     /// Handle an event
-    pub fn event<E: GenericEvent>(&mut self, pos: [f64; 2], size: f64, e: &E) {
+    pub fn event<E: GenericEvent>(
+        &mut self,
+        pos: [f64; 2],
+        size: f64,
+        e: &E,
+    ) {
         use piston::input::{Button, Key, MouseButton};
 
         if let Some(pos) = e.mouse_cursor_args() {
             self.cursor_pos = pos;
+            for button in &mut self.buttons {
+                button.hovered = button.contains(pos);
+            }
+        }
+        if let Some(Button::Keyboard(Key::LShift)) | Some(Button::Keyboard(Key::RShift)) =
+            e.release_args()
+        {
+            self.shift_held = false;
+        }
+        if self.is_paused() {
+            if let Some(Button::Keyboard(Key::P)) = e.press_args() {
+                self.toggle_pause();
+            }
+            return;
         }
         if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            let cursor_pos = self.cursor_pos;
+            if let Some(i) = self.buttons.iter().position(|button| button.contains(cursor_pos)) {
+                // the callback is taken out of the button so it can change the controller
+                let mut on_click =
+                    std::mem::replace(&mut self.buttons[i].on_click, Box::new(|_| {}));
+                on_click(self);
+                self.buttons[i].on_click = on_click;
+                return;
+            }
+
             // find relative position of position to upper left corner
             let x = self.cursor_pos[0] - pos[0];
             let y = self.cursor_pos[1] - pos[1];
 
             if x >= 0.0 && x < size && y >= 0.0 && y < size {
                 // compute cell position
-                let cell_x = (x / size * 9.0) as usize;
-                let cell_y = (y / size * 9.0) as usize;
+                let board_size = self.game_board.size() as f64;
+                let cell_x = (x / size * board_size) as usize;
+                let cell_y = (y / size * board_size) as usize;
+                if self.shift_held {
+                    self.selected_cells.extend(self.selected_cell);
+                    self.selected_cells.insert((cell_x, cell_y));
+                } else {
+                    self.selected_cells.clear();
+                }
                 self.selected_cell = Some((cell_x, cell_y));
             } else {
                 self.selected_cell = None;
+                self.selected_cells.clear();
             }
         }
         if let Some(Button::Keyboard(key)) = e.press_args() {
             match key {
+                Key::LShift | Key::RShift => self.shift_held = true,
                 Key::V => self.note_mode = NoteMode::Value,
                 Key::D => self.note_mode = NoteMode::Deny,
                 Key::M => self.note_mode = NoteMode::Maybe,
@@ -71,17 +309,58 @@ impl GameBoardController {
                     let string = self.game_board.as_byte_string();
                     println!("{}", string);
                 }
-                Key::A => self.game_board.auto_note(),
+                Key::P => self.toggle_pause(),
+                Key::A => {
+                    if let Err(cell) = self.game_board.auto_note() {
+                        println!("{} can't be any value", cell_index_to_rc(cell));
+                    }
+                }
                 Key::C => self.game_board.clear_notes(),
-                Key::S => {
-                    if let Some(solutions) = self.game_board.force_solutions() {
-                        println!("# of solutions: {}", solutions.num_solutions());
-                        let solution = solutions.solution();
-                        self.game_board = solution.clone();
-                    } else {
-                        println!("Could not solve the sudoku puzzle")
+                Key::F1 => {
+                    self.game_settings.auto_note = !self.game_settings.auto_note;
+                    println!("Auto note: {}", self.game_settings.auto_note);
+                    if self.game_settings.auto_note {
+                        if let Err(cell) = self.game_board.auto_note() {
+                            println!("{} can't be any value", cell_index_to_rc(cell));
+                        }
                     }
-                    //self.game_board.solve();
+                }
+                Key::F2 => {
+                    self.game_settings.auto_fill = !self.game_settings.auto_fill;
+                    println!("Auto fill: {}", self.game_settings.auto_fill);
+                }
+                Key::F3 => {
+                    self.game_settings.auto_remove = !self.game_settings.auto_remove;
+                    println!("Auto remove: {}", self.game_settings.auto_remove);
+                }
+                Key::F4 => {
+                    self.game_settings.show_errors = !self.game_settings.show_errors;
+                    println!("Show errors: {}", self.game_settings.show_errors);
+                }
+                Key::F5 => {
+                    self.game_settings.legal_notes_only = !self.game_settings.legal_notes_only;
+                    println!("Legal notes only: {}", self.game_settings.legal_notes_only);
+                }
+                Key::R => {
+                    self.game_board.clear_user_values();
+                    self.mistakes = 0;
+                    self.restart_timer();
+                }
+                Key::S => solve(&mut self.game_board),
+                // the hint's value stays highlighted, instead of being cleared below
+                Key::H if self.show_hint() => return,
+                Key::Up | Key::Down | Key::Left | Key::Right => {
+                    self.selected_cells.clear();
+                    let last = self.game_board.size() - 1;
+                    self.selected_cell = Some(match self.selected_cell {
+                        None => (0, 0),
+                        Some((x, y)) => match key {
+                            Key::Up => (x, y.saturating_sub(1)),
+                            Key::Down => (x, (y + 1).min(last)),
+                            Key::Left => (x.saturating_sub(1), y),
+                            _ => ((x + 1).min(last), y),
+                        },
+                    });
                 }
                 Key::T => {
                     println!("Using technique solver");
@@ -92,16 +371,16 @@ impl GameBoardController {
                             println!(
                                 "Techniques used: {:#?}",
                                 solution
-                                    .moves
+                                    .steps
                                     .into_iter()
-                                    .map(|(_, long)| long)
+                                    .map(|step| step.long_name)
                                     .collect::<Vec<_>>()
                             );
                             self.game_board = solution.solved_board;
                         }
-                        Err(board) => {
+                        Err(partial) => {
                             println!("Could not solve the board using known techniques. Applying techniques to board...");
-                            self.game_board = board;
+                            self.game_board = partial.board;
                         }
                     }
                 }
@@ -109,15 +388,15 @@ impl GameBoardController {
             }
             if let Some(ind) = self.selected_cell {
                 match key {
-                    Key::D1 => self.game_board.set(ind, &self.note_mode, 1),
-                    Key::D2 => self.game_board.set(ind, &self.note_mode, 2),
-                    Key::D3 => self.game_board.set(ind, &self.note_mode, 3),
-                    Key::D4 => self.game_board.set(ind, &self.note_mode, 4),
-                    Key::D5 => self.game_board.set(ind, &self.note_mode, 5),
-                    Key::D6 => self.game_board.set(ind, &self.note_mode, 6),
-                    Key::D7 => self.game_board.set(ind, &self.note_mode, 7),
-                    Key::D8 => self.game_board.set(ind, &self.note_mode, 8),
-                    Key::D9 => self.game_board.set(ind, &self.note_mode, 9),
+                    Key::D1 | Key::NumPad1 => self.set(ind, 1),
+                    Key::D2 | Key::NumPad2 => self.set(ind, 2),
+                    Key::D3 | Key::NumPad3 => self.set(ind, 3),
+                    Key::D4 | Key::NumPad4 => self.set(ind, 4),
+                    Key::D5 | Key::NumPad5 => self.set(ind, 5),
+                    Key::D6 | Key::NumPad6 => self.set(ind, 6),
+                    Key::D7 | Key::NumPad7 => self.set(ind, 7),
+                    Key::D8 | Key::NumPad8 => self.set(ind, 8),
+                    Key::D9 | Key::NumPad9 => self.set(ind, 9),
                     Key::Delete | Key::Backspace => self.game_board.reset(ind),
                     _ => {}
                 }
@@ -125,15 +404,15 @@ impl GameBoardController {
                 //self.selected_cell = None;
             } else {
                 match key {
-                    Key::D1 => self.maybe_highlighted_number = Some(1),
-                    Key::D2 => self.maybe_highlighted_number = Some(2),
-                    Key::D3 => self.maybe_highlighted_number = Some(3),
-                    Key::D4 => self.maybe_highlighted_number = Some(4),
-                    Key::D5 => self.maybe_highlighted_number = Some(5),
-                    Key::D6 => self.maybe_highlighted_number = Some(6),
-                    Key::D7 => self.maybe_highlighted_number = Some(7),
-                    Key::D8 => self.maybe_highlighted_number = Some(8),
-                    Key::D9 => self.maybe_highlighted_number = Some(9),
+                    Key::D1 | Key::NumPad1 => self.maybe_highlighted_number = Some(1),
+                    Key::D2 | Key::NumPad2 => self.maybe_highlighted_number = Some(2),
+                    Key::D3 | Key::NumPad3 => self.maybe_highlighted_number = Some(3),
+                    Key::D4 | Key::NumPad4 => self.maybe_highlighted_number = Some(4),
+                    Key::D5 | Key::NumPad5 => self.maybe_highlighted_number = Some(5),
+                    Key::D6 | Key::NumPad6 => self.maybe_highlighted_number = Some(6),
+                    Key::D7 | Key::NumPad7 => self.maybe_highlighted_number = Some(7),
+                    Key::D8 | Key::NumPad8 => self.maybe_highlighted_number = Some(8),
+                    Key::D9 | Key::NumPad9 => self.maybe_highlighted_number = Some(9),
                     _ => {
                         self.maybe_highlighted_number = None;
                     }
@@ -142,3 +421,39 @@ impl GameBoardController {
         }
     }
 }
+
+/// The buttons shown below the game board
+fn default_buttons() -> Vec<Button> {
+    vec![
+        Button::new("Solve", [0.0; 4], |controller: &mut GameBoardController| {
+            solve(&mut controller.game_board)
+        }),
+        Button::new("Hint", [0.0; 4], |controller: &mut GameBoardController| {
+            controller.show_hint();
+        }),
+        Button::new("Clear Notes", [0.0; 4], |controller: &mut GameBoardController| {
+            controller.game_board.clear_notes()
+        }),
+    ]
+}
+
+/// Replaces the board with its solution, using the technique solver if possible and brute force
+/// otherwise. The board is left unchanged if it can't be solved.
+fn solve(game_board: &mut GameBoard) {
+    let solver = Solver::new(Duration::from_secs(2));
+    match solver.solve(game_board) {
+        Ok(solution) => {
+            println!("Solution found. Difficulty = {:?}", solution.difficulty);
+            *game_board = solution.solved_board;
+        }
+        Err(_) => {
+            let mut solution = game_board.clone();
+            if solution.solve_iterative() {
+                println!("Solution found using brute force");
+                *game_board = solution;
+            } else {
+                println!("Could not solve the sudoku puzzle")
+            }
+        }
+    }
+}
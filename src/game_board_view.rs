@@ -1,13 +1,24 @@
 //! Game board view
 
-use crate::game_board::{CellValue, NoteStatus};
+use crate::game_board::{AffectedComponents, CellValue, NoteStatus};
 use crate::game_board_controller::NoteMode;
 use crate::validity::SudokuCorrectness;
-use crate::{GameBoardController, GameSettings};
+use crate::{GameBoardController, GameSettings, MAX_SIZE, SIZE};
 use graphics::types::Color;
 use graphics::{character::CharacterCache, Context, Graphics, Text};
+/// The space between the board and the edges of the window
+const BOARD_MARGIN: f64 = 10.0;
+/// The size of the board in the default window
+const DEFAULT_BOARD_SIZE: f64 = 400.0;
+/// The width of a candidate in the panel beside the board
+const CANDIDATE_WIDTH: f64 = 15.0;
+/// The space below the board used for text and buttons
+const BELOW_BOARD_HEIGHT: f64 = 120.0;
+
 /// Stores game board view settings.
 pub struct GameBoardViewSettings {
+    /// Color of the window around the board.
+    pub window_background_color: Color,
     /// Position from left-top corner.
     pub position: [f64; 2],
     /// Size of the game board along the horizontal and vertical edge.
@@ -30,12 +41,16 @@ pub struct GameBoardViewSettings {
     pub cell_edge_radius: f64,
     /// The color of the selected cell
     pub selected_cell_background_color: Color,
+    /// The color of the cells that share a row, column, or house with the selected cell
+    pub peer_highlight: Color,
     /// Text color
     pub text_color: Color,
     /// Text color for denies
     pub deny_text_color: Color,
     /// Text colors for maybes
     pub maybe_text_color: Color,
+    /// Strike through denies, so they can be told apart from maybes without relying on color
+    pub distinct_note_glyphs: bool,
     /// Preset Text Color
     pub preset_text_color: Color,
     /// Preset background cell color
@@ -44,15 +59,52 @@ pub struct GameBoardViewSettings {
     pub error_highlight: Color,
     /// Highlight a number
     pub highlight: Color,
+    /// Shade unsolved cells darker the fewer candidates they have
+    pub show_candidate_heatmap: bool,
+    /// The shade of a cell with a single candidate. Cells with more candidates are more transparent
+    pub heatmap_color: Color,
+    /// Where the candidates of the selected cell are listed, relative to the top right corner of
+    /// the board
+    pub candidates_panel_offset: [f64; 2],
+    /// Text color of the candidates of the selected cell
+    pub candidates_panel_color: Color,
+    /// The color of the banner shown when the board is solved
+    pub victory_overlay_color: Color,
+    /// The color of the text of the banner shown when the board is solved
+    pub victory_text_color: Color,
+    /// Background color of buttons
+    pub button_color: Color,
+    /// Background color of a button the mouse is over
+    pub button_hover_color: Color,
+    /// Text color of buttons
+    pub button_text_color: Color,
 }
 
 impl GameBoardViewSettings {
-    /// Creates new game board view settings.
+    /// Creates new game board view settings, using the classic theme.
     pub fn new() -> Self {
+        Self::classic()
+    }
+
+    /// Fits the board in a window of the given size, centered horizontally and leaving room for
+    /// the candidates panel beside the board and the text and buttons below it.
+    pub fn resize(&mut self, window_size: [f64; 2]) {
+        let [width, height] = window_size;
+        // the same space is kept on the left so the board stays centered
+        let panel_width = self.candidates_panel_offset[0] + CANDIDATE_WIDTH;
+        self.size = (width - 2.0 * (BOARD_MARGIN + panel_width))
+            .min(height - BOARD_MARGIN - BELOW_BOARD_HEIGHT)
+            .max(0.0);
+        self.position = [(width - self.size) / 2.0, BOARD_MARGIN];
+    }
+
+    /// The classic theme, with a light purple board.
+    pub fn classic() -> Self {
         let background_color = [0.8, 0.8, 1.0, 1.0];
         Self {
-            position: [10.0; 2],
-            size: 400.0,
+            window_background_color: [1.0; 4],
+            position: [BOARD_MARGIN; 2],
+            size: DEFAULT_BOARD_SIZE,
             background_color,
             border_color: [0.0, 0.0, 0.2, 1.0],
             board_edge_color: [0.0, 0.0, 0.2, 1.0],
@@ -62,17 +114,112 @@ impl GameBoardViewSettings {
             section_edge_radius: 2.0,
             cell_edge_radius: 1.0,
             selected_cell_background_color: [0.9, 0.9, 1.0, 1.0],
+            peer_highlight: [0.85, 0.85, 1.0, 1.0],
             text_color: [0.0, 0.0, 0.1, 1.0],
             deny_text_color: [1.0, 0.0, 0.0, 1.0],
             maybe_text_color: [0.0, 0.0, 0.1, 1.0],
+            distinct_note_glyphs: false,
             preset_text_color: [1.0, 1.0, 1.0, 1.0],
             preset_background_color: from_rgba(94, 34, 107, 1.0),
             error_highlight: [1.0, 0.0, 0.0, 0.3],
             highlight: from_rgba(255, 249, 66, 1.0),
+            show_candidate_heatmap: false,
+            heatmap_color: [0.2, 0.0, 0.4, 0.5],
+            candidates_panel_offset: [15.0, 20.0],
+            candidates_panel_color: from_rgba(94, 34, 107, 1.0),
+            victory_overlay_color: [0.0, 0.0, 0.2, 0.7],
+            victory_text_color: [1.0, 1.0, 1.0, 1.0],
+            button_color: background_color,
+            button_hover_color: [0.9, 0.9, 1.0, 1.0],
+            button_text_color: [0.0, 0.0, 0.1, 1.0],
+        }
+    }
+
+    /// A dark theme, with light text on a dark board.
+    pub fn dark() -> Self {
+        let background_color = from_rgba(40, 42, 54, 1.0);
+        let line_color = from_rgba(150, 150, 180, 1.0);
+        let text_color = from_rgba(230, 230, 240, 1.0);
+        Self {
+            window_background_color: from_rgba(24, 25, 33, 1.0),
+            background_color,
+            border_color: line_color,
+            board_edge_color: line_color,
+            section_edge_color: line_color,
+            cell_edge_color: from_rgba(90, 92, 110, 1.0),
+            selected_cell_background_color: from_rgba(80, 84, 110, 1.0),
+            peer_highlight: from_rgba(55, 58, 76, 1.0),
+            text_color,
+            deny_text_color: from_rgba(255, 110, 110, 1.0),
+            maybe_text_color: text_color,
+            preset_text_color: text_color,
+            preset_background_color: from_rgba(88, 60, 120, 1.0),
+            error_highlight: [1.0, 0.2, 0.2, 0.35],
+            highlight: from_rgba(255, 214, 102, 1.0),
+            candidates_panel_color: from_rgba(190, 160, 230, 1.0),
+            button_color: background_color,
+            button_hover_color: from_rgba(80, 84, 110, 1.0),
+            button_text_color: text_color,
+            ..Self::classic()
+        }
+    }
+
+    /// A high contrast theme, using only black, white, and strong highlight colors.
+    pub fn high_contrast() -> Self {
+        let black = [0.0, 0.0, 0.0, 1.0];
+        let white = [1.0; 4];
+        Self {
+            background_color: white,
+            border_color: black,
+            board_edge_color: black,
+            section_edge_color: black,
+            cell_edge_color: black,
+            board_edge_radius: 4.0,
+            section_edge_radius: 3.0,
+            selected_cell_background_color: from_rgba(0, 200, 255, 1.0),
+            peer_highlight: from_rgba(210, 240, 255, 1.0),
+            text_color: black,
+            deny_text_color: from_rgba(200, 0, 0, 1.0),
+            maybe_text_color: black,
+            distinct_note_glyphs: true,
+            preset_text_color: white,
+            preset_background_color: black,
+            error_highlight: [1.0, 0.0, 0.0, 0.5],
+            highlight: from_rgba(255, 200, 0, 1.0),
+            button_color: white,
+            button_hover_color: from_rgba(0, 200, 255, 1.0),
+            button_text_color: black,
+            ..Self::classic()
         }
     }
 }
 
+impl Default for GameBoardViewSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lists the game settings that are turned on, in the order of the keys that toggle them
+fn settings_status(game_settings: &GameSettings) -> String {
+    let enabled: Vec<&str> = [
+        (game_settings.auto_note, "auto note"),
+        (game_settings.auto_fill, "auto fill"),
+        (game_settings.auto_remove, "auto remove"),
+        (game_settings.show_errors, "errors"),
+        (game_settings.legal_notes_only, "legal notes"),
+    ]
+    .iter()
+    .filter(|(on, _)| *on)
+    .map(|(_, name)| *name)
+    .collect();
+    if enabled.is_empty() {
+        "On (F1-F5): none".to_string()
+    } else {
+        format!("On (F1-F5): {}", enabled.join(", "))
+    }
+}
+
 fn from_rgba(r: u8, g: u8, b: u8, a: f32) -> Color {
     [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a]
 }
@@ -92,7 +239,6 @@ impl GameBoardView {
     /// Draw game board
     pub fn draw<G: Graphics, C>(
         &self,
-        game_settings: &GameSettings,
         controller: &GameBoardController,
         glyphs: &mut C,
         c: &Context,
@@ -110,12 +256,45 @@ impl GameBoardView {
             settings.size,
         ];
 
+        let board = &controller.game_board;
+        let board_size = board.size() as f64;
+        // standard boards at the default size are drawn unscaled
+        let scale = SIZE as f64 / board_size * settings.size / DEFAULT_BOARD_SIZE;
+
         // Draw the background.
         Rectangle::new(settings.background_color).draw(board_rect, &c.draw_state, c.transform, g);
 
-        // Draw selected cell background
+        // Draw the background of the selected cell's peers
         if let Some(ind) = controller.selected_cell {
-            let cell_size = settings.size / 9.0;
+            let cell_size = settings.size / board_size;
+            let affected = AffectedComponents::new(board, ind);
+            let mut peers = vec![];
+            for component in [
+                affected.row().indices_and_cells(),
+                affected.column().indices_and_cells(),
+                affected.house().indices_and_cells(),
+            ] {
+                peers.extend(component.into_iter().map(|(index, _)| index));
+            }
+            for (column, row) in peers {
+                let cell_rect = [
+                    settings.position[0] + column as f64 * cell_size,
+                    settings.position[1] + row as f64 * cell_size,
+                    cell_size,
+                    cell_size,
+                ];
+                Rectangle::new(settings.peer_highlight).draw(
+                    cell_rect,
+                    &c.draw_state,
+                    c.transform,
+                    g,
+                );
+            }
+        }
+
+        // Draw selected cell background
+        for ind in controller.selected_cells.iter().chain(&controller.selected_cell) {
+            let cell_size = settings.size / board_size;
             let pos = [ind.0 as f64 * cell_size, ind.1 as f64 * cell_size];
             let cell_rect = [
                 settings.position[0] + pos[0],
@@ -131,23 +310,44 @@ impl GameBoardView {
             );
         }
 
+        // Shade unsolved cells by how many candidates they have
+        if settings.show_candidate_heatmap {
+            let cell_size = settings.size / board_size;
+            for cell_index in board.iter_unset() {
+                let candidates = board.candidates(cell_index).len();
+                // a cell with no candidates can never be solved, so it's shaded the darkest
+                let pressure =
+                    1.0 - (candidates.max(1) - 1) as f64 / (board.size() - 1).max(1) as f64;
+                let mut color = settings.heatmap_color;
+                color[3] *= pressure as f32;
+                let cell_rect = [
+                    settings.position[0] + cell_index.0 as f64 * cell_size,
+                    settings.position[1] + cell_index.1 as f64 * cell_size,
+                    cell_size,
+                    cell_size,
+                ];
+                Rectangle::new(color).draw(cell_rect, &c.draw_state, c.transform, g);
+            }
+        }
+
         // Draw characters
 
         let text_image = Image::new_color(settings.text_color);
         let preset_text_image = Image::new_color(settings.preset_text_color);
         let highlighted_text_image = Image::new_color(settings.highlight);
-        let cell_size = settings.size / 9.0;
-        for j in 0..9 {
-            for i in 0..9 {
+        let cell_size = settings.size / board_size;
+        let value_font_size = (34.0 * scale) as u32;
+        let note_font_size = (12.0 * scale) as u32;
+        for j in 0..board.size() {
+            for i in 0..board.size() {
                 let pos = [
-                    settings.position[0] + i as f64 * cell_size + 15.0,
-                    settings.position[1] + j as f64 * cell_size + 34.0,
+                    settings.position[0] + i as f64 * cell_size + 15.0 * scale,
+                    settings.position[1] + j as f64 * cell_size + 34.0 * scale,
                 ];
 
-                match controller.game_board.cell_value((i, j)) {
+                match board.cell_value((i, j)) {
                     CellValue::Preset(val) => {
                         {
-                            let cell_size = settings.size / 9.0;
                             let pos = [i as f64 * cell_size, j as f64 * cell_size];
                             let cell_rect = [
                                 settings.position[0] + pos[0],
@@ -165,7 +365,7 @@ impl GameBoardView {
                         }
 
                         let char = GameBoardView::char_for_val(val);
-                        if let Ok(character) = glyphs.character(34, char) {
+                        if let Ok(character) = glyphs.character(value_font_size, char) {
                             let ch_x = pos[0] + character.left();
                             let ch_y = pos[1] - character.top();
 
@@ -195,7 +395,7 @@ impl GameBoardView {
                     }
                     CellValue::Value(val) => {
                         let char = GameBoardView::char_for_val(val);
-                        if let Ok(character) = glyphs.character(34, char) {
+                        if let Ok(character) = glyphs.character(value_font_size, char) {
                             let ch_x = pos[0] + character.left();
                             let ch_y = pos[1] - character.top();
 
@@ -223,20 +423,22 @@ impl GameBoardView {
                             );
                         }
                     }
-                    CellValue::Notes { status } => {
+                    notes @ CellValue::Notes { .. } => {
                         let mut v = 1;
-                        for j in 0..3 {
-                            for i in 0..3 {
-                                if let Some(status) = status[j * 3 + i] {
+                        let note_width = cell_size / board.house_width() as f64;
+                        let note_height = cell_size / board.house_height() as f64;
+                        for j in 0..board.house_height() {
+                            for i in 0..board.house_width() {
+                                if let Some(status) = notes.note_status(v) {
                                     let char = GameBoardView::char_for_val(&v);
-                                    if let Ok(character) = glyphs.character(12, char) {
+                                    if let Ok(character) = glyphs.character(note_font_size, char) {
                                         let ch_x = pos[0]
-                                            + (i as f64 - 1.0) * cell_size / 3.0
+                                            + (i as f64 - 1.0) * note_width
                                             + character.left()
-                                            + 4.0;
-                                        let ch_y = pos[1] + (j as f64 - 1.0) * cell_size / 3.0
+                                            + 4.0 * scale;
+                                        let ch_y = pos[1] + (j as f64 - 1.0) * note_height
                                             - character.top()
-                                            - 7.0;
+                                            - 7.0 * scale;
 
                                         let mut text_image = text_image.src_rect([
                                             character.atlas_offset[0],
@@ -264,6 +466,23 @@ impl GameBoardView {
                                             transform,
                                             g,
                                         );
+
+                                        if let (NoteStatus::Deny, true) =
+                                            (status, settings.distinct_note_glyphs)
+                                        {
+                                            let middle = ch_y + character.atlas_size[1] / 2.0;
+                                            Line::new(settings.deny_text_color, scale).draw(
+                                                [
+                                                    ch_x - 2.0 * scale,
+                                                    middle,
+                                                    ch_x + character.atlas_size[0] + 2.0 * scale,
+                                                    middle,
+                                                ],
+                                                &c.draw_state,
+                                                c.transform,
+                                                g,
+                                            );
+                                        }
                                     }
                                 }
 
@@ -281,23 +500,25 @@ impl GameBoardView {
         let cell_edge = Line::new(settings.cell_edge_color, settings.cell_edge_radius);
         let section_edge = Line::new(settings.section_edge_color, settings.section_edge_radius);
 
-        for i in 0..9 {
-            let x = settings.position[0] + i as f64 / 9.0 * settings.size;
-            let y = settings.position[1] + i as f64 / 9.0 * settings.size;
+        for i in 0..board.size() {
+            let x = settings.position[0] + i as f64 / board_size * settings.size;
+            let y = settings.position[1] + i as f64 / board_size * settings.size;
             let x2 = settings.position[0] + settings.size;
             let y2 = settings.position[1] + settings.size;
 
             let vline = [x, settings.position[1], x, y2];
             let hline = [settings.position[0], y, x2, y];
 
-            // Draw section line
-            if (i % 3) == 0 {
+            // Draw section line, otherwise draw regular line
+            if (i % board.house_width()) == 0 {
                 section_edge.draw(vline, &c.draw_state, c.transform, g);
-                section_edge.draw(hline, &c.draw_state, c.transform, g);
-            }
-            // Draw regular line
-            else {
+            } else {
                 cell_edge.draw(vline, &c.draw_state, c.transform, g);
+            }
+
+            if (i % board.house_height()) == 0 {
+                section_edge.draw(hline, &c.draw_state, c.transform, g);
+            } else {
                 cell_edge.draw(hline, &c.draw_state, c.transform, g);
             }
         }
@@ -310,10 +531,13 @@ impl GameBoardView {
             g,
         );
 
-        let mut text = Text::new(18);
-        let transform = c
-            .transform
-            .trans(25.0, self.settings.size + self.settings.position[0] + 20.0);
+        // the text below the board shrinks with the board, but doesn't grow past the room below it
+        let text_scale = (settings.size / DEFAULT_BOARD_SIZE).min(1.0);
+        let mut text = Text::new((18.0 * text_scale) as u32);
+        let transform = c.transform.trans(
+            self.settings.position[0] + 15.0 * text_scale,
+            self.settings.size + self.settings.position[1] + 20.0,
+        );
 
         match controller.note_mode {
             NoteMode::Value => {
@@ -336,13 +560,69 @@ impl GameBoardView {
             }
         }
 
-        let info_text = Text::new_color(self.settings.text_color, 14);
-        let transform = c
-            .transform
-            .trans(25.0, self.settings.size + self.settings.position[0] + 40.0);
+        text.color = self.settings.text_color;
+        let transform = c.transform.trans(
+            self.settings.position[0] + 240.0 * text_scale,
+            self.settings.size + self.settings.position[1] + 20.0,
+        );
+        text.draw(
+            &format!("Mistakes: {}", controller.mistakes),
+            glyphs,
+            &c.draw_state,
+            transform,
+            g,
+        )
+        .map_err(|_| "Couldn't write text to screen")
+        .unwrap();
+
+        let elapsed = controller.elapsed().as_secs();
+        let mut timer = format!("{:02}:{:02}", elapsed / 60, elapsed % 60);
+        if controller.is_paused() {
+            timer.push_str(" (P)aused");
+        }
+        let transform = c.transform.trans(
+            self.settings.position[0] + 370.0 * text_scale,
+            self.settings.size + self.settings.position[1] + 20.0,
+        );
+        text.draw(&timer, glyphs, &c.draw_state, transform, g)
+            .map_err(|_| "Couldn't write text to screen")
+            .unwrap();
+
+        // List the values the selected cell can still be, one per line beside the board
+        if let Some(ind) = controller.selected_cell {
+            let cell = board.cell_value(ind);
+            let candidates = match cell.maybe_values() {
+                Some(maybes) => maybes,
+                None if cell.as_value().is_none() => board.candidates(ind),
+                None => vec![],
+            };
+            let candidates_text = Text::new_color(settings.candidates_panel_color, 18);
+            for (line, val) in candidates.iter().enumerate() {
+                let transform = c.transform.trans(
+                    settings.position[0] + settings.size + settings.candidates_panel_offset[0],
+                    settings.position[1] + settings.candidates_panel_offset[1] + line as f64 * 22.0,
+                );
+                candidates_text
+                    .draw(
+                        &GameBoardView::char_for_val(val).to_string(),
+                        glyphs,
+                        &c.draw_state,
+                        transform,
+                        g,
+                    )
+                    .map_err(|_| "Couldn't write text to screen")
+                    .unwrap();
+            }
+        }
+
+        let info_text = Text::new_color(self.settings.text_color, (14.0 * text_scale) as u32);
+        let transform = c.transform.trans(
+            self.settings.position[0] + 15.0 * text_scale,
+            self.settings.size + self.settings.position[1] + 40.0,
+        );
         info_text
             .draw(
-                "V = value mode, M = Maybe mode, D = Deny mode",
+                "V = Value, M = Maybe, D = Deny, A = Auto-note, C = Clear notes",
                 glyphs,
                 &c.draw_state,
                 transform,
@@ -351,8 +631,28 @@ impl GameBoardView {
             .map_err(|_| "Couldn't write text to screen")
             .unwrap();
 
-        if game_settings.show_errors {
-            for (column, row) in controller.game_board.invalid_cells() {
+        for button in &controller.buttons {
+            button.draw(settings, glyphs, c, g);
+        }
+
+        // the settings toggled with F1-F5, below the buttons
+        let transform = c.transform.trans(
+            self.settings.position[0] + 15.0 * text_scale,
+            self.settings.size + self.settings.position[1] + 110.0,
+        );
+        Text::new_color(self.settings.text_color, (12.0 * text_scale) as u32)
+            .draw(
+                &settings_status(&controller.game_settings),
+                glyphs,
+                &c.draw_state,
+                transform,
+                g,
+            )
+            .map_err(|_| "Couldn't write text to screen")
+            .unwrap();
+
+        if controller.game_settings.show_errors {
+            for (column, row) in board.invalid_cells() {
                 let pos = [column as f64 * cell_size, row as f64 * cell_size];
 
                 let cell_rect = [
@@ -370,6 +670,32 @@ impl GameBoardView {
                 );
             }
         }
+
+        // Draw the victory banner over the middle of the board
+        if board.is_victory() {
+            let banner_height = settings.size / 4.0;
+            let banner_rect = [
+                settings.position[0],
+                settings.position[1] + (settings.size - banner_height) / 2.0,
+                settings.size,
+                banner_height,
+            ];
+            Rectangle::new(settings.victory_overlay_color).draw(
+                banner_rect,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+
+            let transform = c.transform.trans(
+                settings.position[0] + settings.size / 2.0 - 75.0,
+                settings.position[1] + settings.size / 2.0 + 18.0,
+            );
+            Text::new_color(settings.victory_text_color, 48)
+                .draw("Solved!", glyphs, &c.draw_state, transform, g)
+                .map_err(|_| "Couldn't write text to screen")
+                .unwrap();
+        }
     }
 
     fn char_for_val(val: &u8) -> char {
@@ -383,6 +709,7 @@ impl GameBoardView {
             7 => '7',
             8 => '8',
             9 => '9',
+            &v if v as usize <= MAX_SIZE => (b'A' + (v - 10)) as char,
             v => panic!("Invalid value in game board: {}", v),
         }
     }
@@ -1,7 +1,10 @@
 //! Create a game using a custom formatted byte string
 //!
 //! Byte strings are formatted where the bottom 6 bits of 2 bytes are used to store the x+1, y+1, and val+1
-//! for each cell. This byte string is concluded by a 0,0,0 entry. The first two bits are always 10.
+//! for each cell. The first two bits of each byte are always 01.
+//!
+//! The byte string is concluded by a 0,0,0 entry, which is written as `"@@"`. As x+1 is never 0,
+//! no cell is encoded the same way as the terminator, including the cell at 8,8.
 //!
 //! # Example
 //!
@@ -10,10 +13,15 @@
 //! `"DRMD"`
 
 use crate::game_creator::GameCreator;
+use crate::validity::SudokuCorrectness;
 use crate::GameBoard;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+/// The two bytes that conclude a byte string, a 0,0,0 entry with the first two bits of each byte
+/// set to 01
+pub(crate) const BYTE_STRING_TERMINATOR: [u8; 2] = [0b01000000, 0b01000000];
+
 /// Stores the byte string that represents a game board
 pub struct ByteStringLoader(Vec<u8>);
 
@@ -22,7 +30,7 @@ pub struct ByteStringFormError(String);
 
 impl Display for ByteStringFormError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{}", self.0)
     }
 }
 
@@ -58,7 +66,7 @@ impl GameCreator for ByteStringLoader {
     type Error = ByteStringFormError;
 
     fn into_game(self) -> Result<GameBoard, Self::Error> {
-        if self.0.len() % 2 != 0 {
+        if !self.0.len().is_multiple_of(2) {
             return Err(ByteStringFormError(
                 "Odd number of bytes present in byte string".to_string(),
             ));
@@ -76,10 +84,18 @@ impl GameCreator for ByteStringLoader {
             })?;
 
             let cell = CellBytes::new(upper, lower);
+            // only the terminator has every field set to 0, as cells always store x+1
             if cell.0 == 0 {
                 break;
             }
 
+            if cell.x() == 0 || cell.y() == 0 || cell.val() == 0 {
+                return Err(ByteStringFormError(format!(
+                    "Cell entry {:#06x} has a field of 0",
+                    cell.0
+                )));
+            }
+
             let x = cell.x() as usize - 1;
             let y = cell.y() as usize - 1;
             let val = cell.val() - 1;
@@ -87,6 +103,49 @@ impl GameCreator for ByteStringLoader {
             vector.push(((x, y), val));
         }
 
-        Ok(GameBoard::new().with_presets(vector))
+        let board = GameBoard::new()
+            .try_with_presets(vector)
+            .map_err(|e| ByteStringFormError(e.to_string()))?;
+        if !board.is_valid() {
+            return Err(ByteStringFormError(format!(
+                "Preset cells conflict at {:?}",
+                board.invalid_cells()
+            )));
+        }
+
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CellValue;
+
+    #[test]
+    fn last_cell_is_not_taken_for_the_terminator() {
+        let mut board = GameBoard::new();
+        board[(0, 0)] = CellValue::Preset(1);
+        board[(8, 8)] = CellValue::Preset(9);
+
+        let byte_string = board.as_byte_string();
+        assert!(byte_string.as_bytes().ends_with(&BYTE_STRING_TERMINATOR));
+
+        let loaded = ByteStringLoader::from_string(byte_string)
+            .into_game()
+            .expect("Could not load the byte string");
+        assert_eq!(loaded.cell_value((8, 8)), &CellValue::Preset(9));
+        assert_eq!(loaded, board);
+    }
+
+    #[test]
+    fn only_cell_is_not_taken_for_the_terminator() {
+        let mut board = GameBoard::new();
+        board[(8, 8)] = CellValue::Preset(5);
+
+        let loaded = ByteStringLoader::from_string(board.as_byte_string())
+            .into_game()
+            .expect("Could not load the byte string");
+        assert_eq!(loaded.cell_value((8, 8)), &CellValue::Preset(5));
     }
 }
@@ -0,0 +1,181 @@
+//! Create a game from a grid of characters, with one line for each row
+//!
+//! The digits `1`-`9` are preset cells, and `0`, `.`, or a space are empty cells. Cells can be
+//! separated by `|` or `,`. Empty lines and separator lines made of `-`, `+`, `=`, and `|` are
+//! ignored, but a line of spaces is a row of empty cells.
+//!
+//! # Example
+//!
+//! ```text
+//! 53.|.7.|...
+//! 6..|195|...
+//! .98|...|.6.
+//! ---+---+---
+//! 8..|.6.|..3
+//! ...
+//! ```
+
+use crate::game_creator::GameCreator;
+use crate::validity::SudokuCorrectness;
+use crate::{GameBoard, SIZE};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Stores the grid that represents a game board
+pub struct GridLoader(String);
+
+#[derive(Debug)]
+pub struct GridFormError(String);
+
+impl Display for GridFormError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for GridFormError {}
+
+impl GridLoader {
+    /// Creates a new grid loader from a string
+    pub fn from_string<S: AsRef<str>>(string: S) -> Self {
+        Self(string.as_ref().to_string())
+    }
+
+    /// Tries to create a grid loader from the contents of a file
+    ///
+    /// # Error:
+    /// This function will result in an error if an [IO error] occurs
+    ///
+    /// [IO error]: std::io::Error
+    pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        let mut reader = BufReader::new(file);
+        let mut buffer = String::new();
+        reader.read_to_string(&mut buffer)?;
+        Ok(Self(buffer))
+    }
+}
+
+/// Whether a line doesn't contain any cells, either because it's empty or only separates rows.
+/// Spaces are empty cells, so a line of spaces isn't a separator
+fn is_separator_line(line: &str) -> bool {
+    let line = line.trim_end_matches('\r');
+    line.is_empty()
+        || line.chars().any(|ch| matches!(ch, '-' | '+' | '='))
+            && line
+                .chars()
+                .all(|ch| matches!(ch, '-' | '+' | '=' | '|' | ' '))
+}
+
+impl GameCreator for GridLoader {
+    type Error = GridFormError;
+
+    fn into_game(self) -> Result<GameBoard, Self::Error> {
+        let rows: Vec<&str> = self
+            .0
+            .lines()
+            .filter(|line| !is_separator_line(line))
+            .collect();
+        if rows.len() != SIZE {
+            return Err(GridFormError(format!(
+                "Expected {} rows, found {}",
+                SIZE,
+                rows.len()
+            )));
+        }
+
+        let mut presets = vec![];
+        for (row, line) in rows.into_iter().enumerate() {
+            let cells: Vec<char> = line
+                .trim_end_matches('\r')
+                .chars()
+                .filter(|&ch| ch != '|' && ch != ',')
+                .collect();
+            if cells.len() != SIZE {
+                return Err(GridFormError(format!(
+                    "Expected {} cells in row {}, found {}",
+                    SIZE,
+                    row,
+                    cells.len()
+                )));
+            }
+
+            for (column, ch) in cells.into_iter().enumerate() {
+                match ch {
+                    '0' | '.' | ' ' => {}
+                    '1'..='9' => presets.push(((column, row), ch as u8 - b'0')),
+                    ch => {
+                        return Err(GridFormError(format!(
+                            "Invalid character {:?} in row {}, column {}",
+                            ch, row, column
+                        )))
+                    }
+                }
+            }
+        }
+
+        let board = GameBoard::new()
+            .try_with_presets(presets)
+            .map_err(|e| GridFormError(e.to_string()))?;
+        if !board.is_valid() {
+            return Err(GridFormError(format!(
+                "Preset cells conflict at {:?}",
+                board.invalid_cells()
+            )));
+        }
+
+        Ok(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "\
+53..7....
+6..195...
+.98....6.
+8...6...3
+4..8.3..1
+7...2...6
+.6....28.
+...419..5
+....8..79
+";
+
+    #[test]
+    fn separators_are_ignored() {
+        let separated = "\
+53.|.7.|...
+6..|195|...
+.98|...|.6.
+---+---+---
+8..|.6.|..3
+4..|8.3|..1
+7..|.2.|..6
+ - + - + -
+.6.|...|28.
+...|419|..5
+...|.8.|.79
+";
+        let board = GridLoader::from_string(separated).into_game().unwrap();
+        assert_eq!(board, GridLoader::from_string(GRID).into_game().unwrap());
+    }
+
+    #[test]
+    fn blank_rows_are_empty_cells() {
+        for blank in &["         ", "   |   |   "] {
+            let grid = GRID.replacen("...419..5", blank, 1);
+            let board = GridLoader::from_string(grid).into_game().unwrap();
+            for column in 0..SIZE {
+                assert_eq!(board.cell_value((column, 7)).as_value(), None);
+            }
+            assert_eq!(board.cell_value((1, 8)).as_value(), None);
+            assert_eq!(board.cell_value((4, 8)).as_value(), Some(8));
+        }
+    }
+}
@@ -1,7 +1,10 @@
 //! Create a game using a json formatted string
 
 use crate::game_creator::GameCreator;
-use crate::GameBoard;
+use crate::validity::SudokuCorrectness;
+use crate::{CellIndex, CellValue, GameBoard};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::io::{BufReader, Read};
 use std::path::Path;
@@ -21,6 +24,17 @@ use std::path::Path;
 ///     .
 /// ]
 /// ```
+///
+/// Entries can optionally have `"preset": false` to load the cell as a user entered value instead
+/// of a preset. Instead of a value, an entry can have a list of notes, which are loaded as
+/// possible values for the cell:
+/// ```json
+/// {
+///     "x": <column>
+///     "y": <row>
+///     "notes": [<value>, ...]
+/// }
+/// ```
 pub struct JSONLoader(String);
 
 impl JSONLoader {
@@ -44,26 +58,166 @@ impl JSONLoader {
     }
 }
 
+/// A single cell of a game board in the JSON format
 #[derive(Deserialize, Serialize)]
-struct JSONCellEntry {
+pub(crate) struct JSONCellEntry {
     x: usize,
     y: usize,
-    val: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    val: Option<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<u8>,
+    #[serde(default = "default_preset", skip_serializing_if = "is_preset")]
+    preset: bool,
+}
+
+fn default_preset() -> bool {
+    true
+}
+
+fn is_preset(preset: &bool) -> bool {
+    *preset
+}
+
+impl JSONCellEntry {
+    /// Creates an entry for the cell at (x, y), if the cell isn't empty
+    pub(crate) fn from_cell(x: usize, y: usize, cell: &CellValue) -> Option<Self> {
+        let (val, notes, preset) = match cell {
+            &CellValue::Preset(val) => (Some(val), vec![], true),
+            &CellValue::Value(val) => (Some(val), vec![], false),
+            CellValue::Notes { .. } => (None, cell.maybe_values()?, true),
+            CellValue::Empty => return None,
+        };
+        if val.is_none() && notes.is_empty() {
+            return None;
+        }
+        Some(JSONCellEntry {
+            x,
+            y,
+            val,
+            notes,
+            preset,
+        })
+    }
+
+    /// The cell value this entry represents
+    fn cell_value(&self) -> CellValue {
+        match self.val {
+            Some(val) if self.preset => CellValue::Preset(val),
+            Some(val) => CellValue::Value(val),
+            None if self.notes.is_empty() => CellValue::Empty,
+            None => {
+                let maybe = self
+                    .notes
+                    .iter()
+                    .fold(0, |maybe, &note| maybe | 1 << (note - 1));
+                CellValue::Notes { maybe, deny: 0 }
+            }
+        }
+    }
+}
+
+/// Contains error information for the json loader
+#[derive(Debug)]
+pub enum JSONFormError {
+    /// The string isn't json, or isn't formatted as a list of cells
+    Parse(serde_json::Error),
+    /// The preset cells conflict with each other
+    ConflictingCells(Vec<CellIndex>),
+    /// A cell is outside of the board
+    CellOutOfBounds(CellIndex),
+    /// A cell's value or note isn't between 1 and the size of the board
+    InvalidValue(CellIndex, u8),
+}
+
+impl Display for JSONFormError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl Error for JSONFormError {}
+
+impl From<serde_json::Error> for JSONFormError {
+    fn from(e: serde_json::Error) -> Self {
+        JSONFormError::Parse(e)
+    }
 }
 
 impl GameCreator for JSONLoader {
-    type Error = serde_json::Error;
+    type Error = JSONFormError;
 
     fn into_game(self) -> Result<GameBoard, Self::Error> {
         let values: Vec<JSONCellEntry> = serde_json::from_str(self.0.as_str())?;
 
-        let iter = values.into_iter().map(|entry| {
-            let JSONCellEntry { x, y, val } = entry;
-            ((x, y), val)
-        });
+        let mut board = GameBoard::new();
+        for entry in values {
+            let index = (entry.x, entry.y);
+            if entry.x >= board.size() || entry.y >= board.size() {
+                return Err(JSONFormError::CellOutOfBounds(index));
+            }
+            if let Some(&val) = entry
+                .val
+                .iter()
+                .chain(&entry.notes)
+                .find(|&&val| val == 0 || val as usize > board.size())
+            {
+                return Err(JSONFormError::InvalidValue(index, val));
+            }
+            board.cells[entry.y][entry.x] = entry.cell_value();
+        }
 
-        let board = GameBoard::new().with_presets(iter);
+        // values entered by the player can be mistakes, so only the presets have to agree
+        let givens = board.givens_only();
+        if !givens.is_valid() {
+            return Err(JSONFormError::ConflictingCells(givens.invalid_cells()));
+        }
 
         Ok(board)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_conflicting_user_value() {
+        let json = r#"[
+            {"x": 0, "y": 0, "val": 5},
+            {"x": 4, "y": 0, "val": 5, "preset": false}
+        ]"#;
+        let board = JSONLoader::from_string(json)
+            .into_game()
+            .expect("A user mistake shouldn't stop the board from loading");
+        assert_eq!(board.cell_value((0, 0)), &CellValue::Preset(5));
+        assert_eq!(board.cell_value((4, 0)), &CellValue::Value(5));
+        assert!(!board.is_valid());
+    }
+
+    #[test]
+    fn rejects_conflicting_presets() {
+        let json = r#"[
+            {"x": 0, "y": 0, "val": 5},
+            {"x": 4, "y": 0, "val": 5}
+        ]"#;
+        match JSONLoader::from_string(json).into_game() {
+            Err(JSONFormError::ConflictingCells(cells)) => {
+                assert!(cells.contains(&(0, 0)));
+                assert!(cells.contains(&(4, 0)));
+            }
+            _ => panic!("Conflicting presets should be rejected"),
+        }
+    }
+
+    #[test]
+    fn saved_mistake_loads_again() {
+        let mut board = GameBoard::new();
+        board[(0, 0)] = CellValue::Preset(5);
+        board[(0, 4)] = CellValue::Value(5);
+        let loaded = JSONLoader::from_string(board.to_json())
+            .into_game()
+            .expect("Could not load a saved board");
+        assert_eq!(loaded, board);
+    }
+}
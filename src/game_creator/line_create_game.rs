@@ -0,0 +1,80 @@
+//! Create a game from a single line of characters
+//!
+//! Each character is a cell, going left to right through each row from top to bottom. The digits
+//! `1`-`9` are preset cells, and `0` or `.` are empty cells.
+//!
+//! # Example
+//!
+//! A board with only cell 0,0 set to 5 and cell 2,1 set to 3 would be `"5.........3"` followed by
+//! 70 more `.`s
+
+use crate::game_creator::GameCreator;
+use crate::validity::SudokuCorrectness;
+use crate::{GameBoard, SIZE};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Stores the line that represents a game board
+pub struct LineLoader(String);
+
+#[derive(Debug)]
+pub struct LineFormError(String);
+
+impl Display for LineFormError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for LineFormError {}
+
+impl LineLoader {
+    /// Creates a new line loader from a string
+    pub fn from_string<S: AsRef<str>>(string: S) -> Self {
+        Self(string.as_ref().trim().to_string())
+    }
+}
+
+impl GameCreator for LineLoader {
+    type Error = LineFormError;
+
+    fn into_game(self) -> Result<GameBoard, Self::Error> {
+        let chars: Vec<char> = self.0.chars().collect();
+        if chars.len() != SIZE * SIZE {
+            return Err(LineFormError(format!(
+                "Expected {} characters, found {}",
+                SIZE * SIZE,
+                chars.len()
+            )));
+        }
+
+        let mut presets = vec![];
+        for (index, ch) in chars.into_iter().enumerate() {
+            match ch {
+                '0' | '.' => {}
+                '1'..='9' => {
+                    let val = ch as u8 - b'0';
+                    presets.push(((index % SIZE, index / SIZE), val));
+                }
+                ch => {
+                    return Err(LineFormError(format!(
+                        "Invalid character {:?} at position {}",
+                        ch, index
+                    )))
+                }
+            }
+        }
+
+        let board = GameBoard::new()
+            .try_with_presets(presets)
+            .map_err(|e| LineFormError(e.to_string()))?;
+        if !board.is_valid() {
+            return Err(LineFormError(format!(
+                "Preset cells conflict at {:?}",
+                board.invalid_cells()
+            )));
+        }
+
+        Ok(board)
+    }
+}
@@ -5,18 +5,31 @@
 //!     2. creating a game from a random generator
 //!     3. creating a new game that can be exported
 //!     4. creating a game from a json
+//!     5. loading a game from a single line of digits
+//!     6. loading a game from a grid of digits, with one line for each row
 
 use crate::GameBoard;
 use std::error::Error;
 
 mod json_create_game;
-pub use json_create_game::JSONLoader;
+pub(crate) use json_create_game::JSONCellEntry;
+pub use json_create_game::{JSONFormError, JSONLoader};
 
 mod byte_string_create_game;
+pub(crate) use byte_string_create_game::BYTE_STRING_TERMINATOR;
 pub use byte_string_create_game::ByteStringLoader;
 
+mod line_create_game;
+pub use line_create_game::LineLoader;
+
+mod grid_create_game;
+pub use grid_create_game::GridLoader;
+
 mod random_create_game;
-pub use random_create_game::{RandomCreatorError, RandomLoader};
+pub use random_create_game::{
+    generate_batch, GenerationPhase, BATCH_ATTEMPTS_PER_BOARD, GenerationProgress, RandomCreatorError, RandomLoader,
+    TARGET_DIFFICULTY_ATTEMPTS,
+};
 
 /// Helper trait for generating games
 pub trait GameCreator {
@@ -1,5 +1,6 @@
 //! Create a game using a random number generator.
 
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::time::{Instant, Duration};
@@ -10,35 +11,221 @@ use rand_pcg::Pcg64;
 
 use crate::game_board_controller::NoteMode;
 use crate::game_creator::GameCreator;
-use crate::validity::{can_be_completed, SudokuCorrectness};
+use crate::validity::{count_solutions_within, SudokuCorrectness};
 use crate::{CellIndex, CellValue, GameBoard};
-use crate::advanced_solver::Solver;
-use std::io::{stdout, Write};
+use crate::advanced_solver::{Difficulty, Solver};
 
+/// Prints a line of progress information if the loader is verbose
+macro_rules! verbose {
+    ($loader:expr, $($arg:tt)*) => {
+        if $loader.verbose {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// The steps of generating a board
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GenerationPhase {
+    /// Filling in every cell to create a solution
+    Solving,
+    /// Removing cells from the solution while it stays unique
+    Removing,
+}
 
-/// Contains a random generator to create a board
-pub struct RandomLoader<R: Rng> {
+/// How far along generating a board is
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GenerationProgress {
+    /// The current step
+    pub phase: GenerationPhase,
+    /// How much of the current step is done, from 0.0 to 1.0
+    pub fraction: f64,
+}
+
+/// Contains a random generator to create a board. The progress callback can borrow from its
+/// surroundings for the lifetime `'a`
+pub struct RandomLoader<'a, R: Rng> {
     rng: R,
     /// The number of starting cells
     pub num_starting_cells: usize,
+    target_difficulty: Option<Difficulty>,
+    symmetric: bool,
+    verbose: bool,
+    progress: Option<Box<dyn FnMut(GenerationProgress) + 'a>>,
+    deadline: Option<Duration>,
+    max_attempts: usize,
 }
 
-impl RandomLoader<ThreadRng> {
+impl<'a> RandomLoader<'a, ThreadRng> {
     /// Creates a new random generator to create a board
     pub fn new() -> Self {
         RandomLoader {
             rng: thread_rng(),
             num_starting_cells: 24,
+            target_difficulty: None,
+            symmetric: false,
+            verbose: false,
+            progress: None,
+            deadline: None,
+            max_attempts: TARGET_DIFFICULTY_ATTEMPTS,
         }
     }
 }
 
-impl RandomLoader<Pcg64> {
+impl Default for RandomLoader<'_, ThreadRng> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> RandomLoader<'a, Pcg64> {
     /// use a preset seed for the rng
     pub fn from_seed(seed: u64) -> Self {
         RandomLoader {
             rng: Pcg64::seed_from_u64(seed),
             num_starting_cells: 24,
+            target_difficulty: None,
+            symmetric: false,
+            verbose: false,
+            progress: None,
+            deadline: None,
+            max_attempts: TARGET_DIFFICULTY_ATTEMPTS,
+        }
+    }
+}
+
+/// The number of boards a [RandomLoader] generates while looking for one with its target
+/// difficulty and number of starting cells before giving up, unless it's changed with
+/// [with_max_attempts](RandomLoader::with_max_attempts)
+pub const TARGET_DIFFICULTY_ATTEMPTS: usize = 100;
+
+/// The fewest starting cells a sudoku board can have and still have a unique solution
+pub const MIN_STARTING_CELLS: usize = 17;
+
+impl<'a, R: Rng> RandomLoader<'a, R> {
+    /// Sets the number of starting cells. Must be between 17 and 81, otherwise creating the game
+    /// fails with [InvalidStartingCells].
+    ///
+    /// Cells are only removed while the solution stays unique, so a board can get stuck with more
+    /// starting cells than this. That board is thrown away and counts as one of the
+    /// [max attempts](RandomLoader::with_max_attempts), so very few starting cells, such as 17, are
+    /// likely to fail with [TooManyAttempts].
+    ///
+    /// [InvalidStartingCells]: RandomCreatorError::InvalidStartingCells
+    /// [TooManyAttempts]: RandomCreatorError::TooManyAttempts
+    pub fn with_givens(mut self, num_starting_cells: usize) -> Self {
+        self.num_starting_cells = num_starting_cells;
+        self
+    }
+
+    /// Only create boards that the [Solver] rates as the given difficulty. Boards are generated
+    /// until one matches, so this can take much longer than generating a single board. Fails with
+    /// [TooManyAttempts] if none of the first [TARGET_DIFFICULTY_ATTEMPTS] boards match.
+    ///
+    /// [Solver]: crate::advanced_solver::Solver
+    /// [TooManyAttempts]: RandomCreatorError::TooManyAttempts
+    pub fn with_target_difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.target_difficulty = Some(difficulty);
+        self
+    }
+
+    /// Sets how many boards are generated while looking for one with the target difficulty and
+    /// the right number of starting cells before giving up. At least one board is always
+    /// generated.
+    pub fn with_max_attempts(mut self, max_attempts: usize) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Whether the starting cells should have 180° rotational symmetry, like most published
+    /// puzzles. When set, cells are removed in pairs that mirror each other across the center, and
+    /// the center cell is only removed if there's an even number of starting cells.
+    pub fn symmetric(mut self, symmetric: bool) -> Self {
+        self.symmetric = symmetric;
+        self
+    }
+
+    /// Whether progress should be printed to stdout while generating the board. Off by default.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Calls a function with the progress of generating the board whenever it changes
+    pub fn with_progress<F: FnMut(GenerationProgress) + 'a>(mut self, callback: F) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Gives up on creating the game with [Timeout] if it takes longer than the given duration.
+    ///
+    /// The deadline is best-effort: it's checked between steps of generating the board, and
+    /// searches for solutions are given only the time that's left, but rating a board for a target
+    /// difficulty isn't interrupted, so the error can come a little after the deadline.
+    ///
+    /// [Timeout]: RandomCreatorError::Timeout
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Fails with [Timeout] if generation started longer ago than the deadline.
+    ///
+    /// [Timeout]: RandomCreatorError::Timeout
+    fn check_deadline(&self, start: Instant) -> Result<(), RandomCreatorError> {
+        match self.deadline {
+            Some(deadline) if start.elapsed() >= deadline => Err(RandomCreatorError::Timeout),
+            _ => Ok(()),
+        }
+    }
+
+    /// The time left before the deadline, for generation that started at `start`
+    fn remaining(&self, start: Instant) -> Duration {
+        match self.deadline {
+            Some(deadline) => deadline.saturating_sub(start.elapsed()),
+            None => Duration::MAX,
+        }
+    }
+
+    /// Counts the solutions of the board up to `limit`, failing with [Timeout] if the deadline
+    /// passes first
+    ///
+    /// [Timeout]: RandomCreatorError::Timeout
+    fn count_solutions(
+        &self,
+        board: &GameBoard,
+        limit: usize,
+        start: Instant,
+    ) -> Result<usize, RandomCreatorError> {
+        count_solutions_within(board, limit, self.remaining(start))
+            .ok_or(RandomCreatorError::Timeout)
+    }
+
+    /// Reports the progress of generating the board, if there is a progress callback
+    fn report(&mut self, phase: GenerationPhase, done: usize, total: usize) {
+        if let Some(progress) = &mut self.progress {
+            let fraction = if total == 0 {
+                1.0
+            } else {
+                done as f64 / total as f64
+            };
+            progress(GenerationProgress { phase, fraction });
+        }
+    }
+
+    /// Whether the board is rated as the target difficulty, if there is one
+    fn matches_target_difficulty(&self, board: &GameBoard) -> bool {
+        match self.target_difficulty {
+            None => true,
+            Some(target) => {
+                // never time out, so the rating doesn't depend on how fast the machine is
+                let solver = Solver::new(Duration::MAX);
+                match solver.solve(board) {
+                    Ok(solution) => solution.difficulty == target,
+                    // boards that can't be solved with techniques can't be rated
+                    Err(_) => false,
+                }
+            }
         }
     }
 }
@@ -52,26 +239,31 @@ pub enum RandomCreatorError {
     SelectedCellAlreadySet,
     /// The created board couldn't be undone to make a new board
     CorruptedBoardIntractable,
+    /// The number of starting cells can't be used to create a board with a unique solution
+    InvalidStartingCells(usize),
+    /// Creating the board took longer than the deadline
+    Timeout,
+    /// Too many boards were generated without finding enough that matched the requirements
+    TooManyAttempts(usize),
+    /// Cells couldn't be removed down to the number of starting cells while keeping the solution
+    /// unique. Holds the number of starting cells the board was left with
+    TooManyStartingCells(usize),
 }
 
-fn sample_from_vec<'a, T, R : Rng>(vector: &'a Vec<T>, rng: &mut R) -> Option<&'a T> {
-    let len = vector.len();
-    if len == 0 {
-        return None;
-    }
-
-    let index = rng.gen_range(0..len);
-    vector.get(index)
+/// Picks an index below `len`. Sampled as a `u32` instead of a `usize` so that the same seed
+/// produces the same values on both 32 and 64 bit platforms.
+fn gen_index<R: Rng>(rng: &mut R, len: usize) -> usize {
+    rng.gen_range(0..len as u32) as usize
 }
 
-fn take_from_vec<T, R : Rng>(vector: &mut Vec<T>, rng: &mut R) -> Option<T> {
+fn sample_from_vec<'a, T, R : Rng>(vector: &'a [T], rng: &mut R) -> Option<&'a T> {
     let len = vector.len();
     if len == 0 {
         return None;
     }
 
-    let index = rng.gen_range(0..len);
-    Some(vector.remove(index))
+    let index = gen_index(rng, len);
+    vector.get(index)
 }
 
 impl Display for RandomCreatorError {
@@ -91,42 +283,85 @@ impl RandomMove {
 
     fn undo_move(&self, board: &mut GameBoard, available_cells: &mut Vec<CellIndex>) {
         board.reset(self.0);
-        board.auto_note();
+        let _ = board.auto_note();
         available_cells.push(self.0);
     }
 }
 
-impl<R: Rng> GameCreator for RandomLoader<R> {
+impl<R: Rng> GameCreator for RandomLoader<'_, R> {
     type Error = RandomCreatorError;
 
     fn into_game(mut self) -> Result<GameBoard, Self::Error> {
+        if self.num_starting_cells < MIN_STARTING_CELLS || self.num_starting_cells > 81 {
+            return Err(RandomCreatorError::InvalidStartingCells(
+                self.num_starting_cells,
+            ));
+        }
+
+        let start = Instant::now();
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.generate(start) {
+                Ok(game_board) => {
+                    if self.matches_target_difficulty(&game_board) {
+                        return Ok(game_board);
+                    }
+                    verbose!(self, "Generated board did not match the target difficulty");
+                }
+                Err(RandomCreatorError::TooManyStartingCells(givens)) => {
+                    verbose!(self, "Generated board was stuck at {} starting cells", givens);
+                }
+                Err(e) => return Err(e),
+            }
+            if attempts >= self.max_attempts {
+                return Err(RandomCreatorError::TooManyAttempts(attempts));
+            }
+            verbose!(self, "Regenerating...");
+        }
+    }
+}
+
+impl<R: Rng> RandomLoader<'_, R> {
+    /// Creates a random, complete, and valid board, without removing any cells to make it into a
+    /// puzzle
+    pub fn full_solution(mut self) -> Result<GameBoard, RandomCreatorError> {
+        let solution = self.generate_solution(Instant::now())?;
+        if solution.is_complete() && solution.is_valid() {
+            Ok(solution)
+        } else {
+            Err(RandomCreatorError::InvalidBoardCreated)
+        }
+    }
+
+    /// Generates a complete and valid board. Generation started at `start`, for checking the
+    /// deadline
+    fn generate_solution(&mut self, start: Instant) -> Result<GameBoard, RandomCreatorError> {
         let mut game_board = GameBoard::new();
-        game_board.auto_note(); // create all notes
+        let _ = game_board.auto_note(); // create all notes
 
 
         let mut available_cells: Vec<CellIndex> = (0..9)
-            .into_iter()
-            .flat_map(move |i| (0..9).into_iter().map(move |j| (j, i)))
+            .flat_map(move |i| (0..9).map(move |j| (j, i)))
             .collect();
 
         let mut move_stack: Vec<RandomMove> = vec![];
 
         let start_initial_board_start = Instant::now();
         while game_board.is_valid() && !game_board.is_complete() {
-            game_board.auto_note();
+            self.check_deadline(start)?;
+            let _ = game_board.auto_note();
             let next_cell = available_cells.pop().unwrap();
 
             let cell = game_board[next_cell];
-            if let CellValue::Notes { status: _ } = cell {
+            if let CellValue::Notes { .. } = cell {
                 let maybe_values = cell.maybe_values().unwrap();
 
                 if maybe_values.is_empty() {
-                    println!("I'm not sure how this wasn't already detected");
+                    verbose!(self, "I'm not sure how this wasn't already detected");
                     return Ok(game_board);
                 }
 
-                print!("Maybe values for {:?}: {:?}", next_cell, maybe_values);
-
                 let value = *sample_from_vec(&maybe_values, &mut self.rng).unwrap();
 
                 let next_move = RandomMove(next_cell, value);
@@ -135,24 +370,32 @@ impl<R: Rng> GameCreator for RandomLoader<R> {
                 move_stack.push(next_move);
 
                 // game_board.set(next_cell, &NoteMode::Value, value);
-                println!(", set to {}", value);
+                verbose!(
+                    self,
+                    "Maybe values for {:?}: {:?}, set to {}",
+                    next_cell,
+                    maybe_values,
+                    value
+                );
             } else {
                 return Err(RandomCreatorError::SelectedCellAlreadySet);
             }
 
-            println!("Checking if can be completed...");
+            verbose!(self, "Checking if can be completed...");
             let time = Instant::now();
-            while !can_be_completed(&game_board) {
+            while self.count_solutions(&game_board, 1, start)? == 0 {
+                self.check_deadline(start)?;
                 match move_stack.pop() {
                     None => return Err(RandomCreatorError::CorruptedBoardIntractable),
                     Some(my_move) => {
-                        println!("Undoing {:?} <- {}", my_move.0, my_move.1);
+                        verbose!(self, "Undoing {:?} <- {}", my_move.0, my_move.1);
                         my_move.undo_move(&mut game_board, &mut available_cells);
                     }
                 }
             }
             let duration = time.elapsed();
-            println!("Done in {:.3} sec", duration.as_secs_f64());
+            verbose!(self, "Done in {:.3} sec", duration.as_secs_f64());
+            self.report(GenerationPhase::Solving, 81 - available_cells.len(), 81);
         }
 
         // after generating all values, if the board is not complete and valid, an error occured
@@ -160,7 +403,8 @@ impl<R: Rng> GameCreator for RandomLoader<R> {
             return Err(RandomCreatorError::InvalidBoardCreated);
         }
 
-        println!(
+        verbose!(
+            self,
             "Initial board created in {:.3} sec",
             start_initial_board_start.elapsed().as_secs_f64()
         );
@@ -172,11 +416,11 @@ impl<R: Rng> GameCreator for RandomLoader<R> {
         for _ in 0..num_swaps {
             let swap_column: bool = self.rng.gen();
 
-            let base_index = self.rng.gen_range(0usize..3) * 3;
+            let base_index = gen_index(&mut self.rng, 3) * 3;
 
-            let index1 = self.rng.gen_range(0usize..3);
+            let index1 = gen_index(&mut self.rng, 3);
             let index2 = loop {
-                let v = self.rng.gen_range(0usize..3);
+                let v = gen_index(&mut self.rng, 3);
                 if v != index1 {
                     break v;
                 }
@@ -187,62 +431,93 @@ impl<R: Rng> GameCreator for RandomLoader<R> {
                 true => {
                     let col1 = base_index + index1;
                     let col2 = base_index + index2;
-                    println!("Swapping columns {} and {}", col1, col2);
+                    verbose!(self, "Swapping columns {} and {}", col1, col2);
                     game_board.swap_columns(col1, col2);
                 }
                 // swap rows
                 false => {
                     let row1 = base_index + index1;
                     let row2 = base_index + index2;
-                    println!("Swapping rows {} and {}", row1, row2);
+                    verbose!(self, "Swapping rows {} and {}", row1, row2);
                     game_board.swap_rows(row1, row2);
                 }
             }
         }
 
+        Ok(game_board)
+    }
+
+    /// Generates a single board, regardless of its difficulty. Generation started at `start`, for
+    /// checking the deadline
+    fn generate(&mut self, start: Instant) -> Result<GameBoard, RandomCreatorError> {
+        let mut game_board = self.generate_solution(start)?;
+
         let mut cells_removed = 0;
 
         let mut available_cells: Vec<CellIndex> = (0..9)
-            .into_iter()
-            .flat_map(move |i| (0..9).into_iter().map(move |j| (j, i)))
+            .flat_map(move |i| (0..9).map(move |j| (j, i)))
             .collect();
+        if self.symmetric {
+            // only keep one cell of each symmetric pair, the other is removed alongside it. The
+            // center mirrors itself, so it's the only way to remove an odd number of cells. It's
+            // removed first in that case, since a solution missing a single cell is always unique
+            available_cells.truncate(40);
+            if (81 - self.num_starting_cells) % 2 == 1 {
+                game_board.reset((4, 4));
+                cells_removed += 1;
+            }
+        }
 
         let mut buffer: Vec<CellIndex> = vec![];
 
-        print!("Find Cell 0: ");
-        stdout().flush().unwrap();
-
         let mut search_start = Instant::now();
-        let solution = game_board.clone();
 
         while cells_removed < (81 - self.num_starting_cells) {
+            self.check_deadline(start)?;
             if available_cells.is_empty() {
                 break;
             }
-            let next_index = self.rng.gen_range(0..available_cells.len());
+            let next_index = gen_index(&mut self.rng, available_cells.len());
             let index = available_cells.remove(next_index);
 
+            let mut to_remove = vec![index];
+            let mirrored = (8 - index.0, 8 - index.1);
+            if self.symmetric && mirrored != index {
+                to_remove.push(mirrored);
+            }
+
+            if cells_removed + to_remove.len() > 81 - self.num_starting_cells {
+                buffer.push(index);
+                continue;
+            }
+
             let mut next = game_board.clone();
-            let value = next[index].as_value().unwrap();
+            for &cell in &to_remove {
+                next.reset(cell);
+            }
 
-            next.reset(index);
+            let unique = self.count_solutions(&next, 2, start)? == 1;
 
-            if next.try_solve_restricted(index, value).is_none() {
-                println!(" Found in {:.3} sec.", search_start.elapsed().as_secs_f64());
+            if unique {
+                verbose!(self, "Found in {:.3} sec.", search_start.elapsed().as_secs_f64());
                 search_start = Instant::now();
-                println!(
+                verbose!(
+                    self,
                     "Cell Removal Progress: {:3.2}% ({}/{})",
                     cells_removed as f64 / (81 - self.num_starting_cells) as f64 * 100.0,
                     cells_removed,
                     81 - self.num_starting_cells
                 );
                 game_board = next;
-                cells_removed += 1;
+                cells_removed += to_remove.len();
                 available_cells.extend(buffer);
                 buffer = vec![];
+                self.report(
+                    GenerationPhase::Removing,
+                    cells_removed,
+                    81 - self.num_starting_cells,
+                );
             } else {
-                print!("|");
-                stdout().flush().unwrap();
                 buffer.push(index);
             }
 
@@ -278,28 +553,185 @@ impl<R: Rng> GameCreator for RandomLoader<R> {
 
              */
         }
-        println!();
-        println!(
+        verbose!(
+            self,
             "Cell Removal Progress: {:3.2}%",
             cells_removed as f64 / (81 - self.num_starting_cells) as f64 * 100.0
         );
-        println!("{:#?}", game_board);
-        for cell in (0usize..9)
-            .into_iter()
-            .flat_map(move |i| (0usize..9).into_iter().map(move |j| (j, i)))
-        {
+        verbose!(self, "{:#?}", game_board);
+        if cells_removed < 81 - self.num_starting_cells {
+            return Err(RandomCreatorError::TooManyStartingCells(81 - cells_removed));
+        }
+        for cell in (0usize..9).flat_map(move |i| (0usize..9).map(move |j| (j, i))) {
             if let CellValue::Value(v) = game_board[cell] {
                 game_board[cell] = CellValue::Preset(v);
             }
         }
 
-        println!("Number of starting cells: {}", 81 - cells_removed);
-        println!(
+        verbose!(self, "Number of starting cells: {}", 81 - cells_removed);
+        verbose!(
+            self,
             "Generated board in {:.3} sec.\nSeed: {}",
-            start_initial_board_start.elapsed().as_secs_f64(),
+            start.elapsed().as_secs_f64(),
             game_board.as_byte_string()
         );
 
         Ok(game_board)
     }
 }
+
+/// The starting cells of a board with the values relabeled in the order they first appear, so
+/// boards that only differ by swapping values have the same form
+fn canonical_form(board: &GameBoard) -> Vec<u8> {
+    let mut labels: Vec<u8> = vec![];
+    board
+        .into_iter()
+        .map(|cell| match cell {
+            CellValue::Preset(val) | CellValue::Value(val) => {
+                match labels.iter().position(|label| label == val) {
+                    Some(position) => position as u8 + 1,
+                    None => {
+                        labels.push(*val);
+                        labels.len() as u8
+                    }
+                }
+            }
+            _ => 0,
+        })
+        .collect()
+}
+
+/// The number of boards [generate_batch] can generate for each board it returns before giving up
+pub const BATCH_ATTEMPTS_PER_BOARD: usize = 100;
+
+/// Creates `count` boards with `givens` starting cells each, using a single rng seeded with
+/// `seed` so the same arguments always create the same boards. No two boards are the same, even
+/// after relabeling their values. If there's a target difficulty, only boards the [Solver] rates
+/// as that difficulty are kept.
+///
+/// Fails with [TooManyAttempts] if more than [BATCH_ATTEMPTS_PER_BOARD] boards are generated for
+/// each board requested, such as when no board with that many starting cells can be the target
+/// difficulty.
+///
+/// [Solver]: crate::advanced_solver::Solver
+/// [TooManyAttempts]: RandomCreatorError::TooManyAttempts
+pub fn generate_batch(
+    count: usize,
+    givens: usize,
+    seed: u64,
+    target_difficulty: Option<Difficulty>,
+) -> Result<Vec<GameBoard>, RandomCreatorError> {
+    if !(MIN_STARTING_CELLS..=81).contains(&givens) {
+        return Err(RandomCreatorError::InvalidStartingCells(givens));
+    }
+
+    let mut loader = RandomLoader::from_seed(seed).with_givens(givens);
+    loader.target_difficulty = target_difficulty;
+    let max_attempts = count.saturating_mul(BATCH_ATTEMPTS_PER_BOARD);
+    let mut forms: HashSet<Vec<u8>> = HashSet::new();
+    let mut boards = vec![];
+    let mut attempts = 0;
+    while boards.len() < count {
+        if attempts >= max_attempts {
+            return Err(RandomCreatorError::TooManyAttempts(attempts));
+        }
+        attempts += 1;
+
+        let board = match loader.generate(Instant::now()) {
+            Ok(board) => board,
+            Err(_) => continue,
+        };
+        if loader.matches_target_difficulty(&board) && forms.insert(canonical_form(&board)) {
+            boards.push(board);
+        }
+    }
+    Ok(boards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreachable_difficulty_gives_up() {
+        let result = RandomLoader::from_seed(0)
+            .with_target_difficulty(Difficulty::Pro)
+            .with_max_attempts(2)
+            .into_game();
+        match result {
+            Err(RandomCreatorError::TooManyAttempts(attempts)) => assert_eq!(attempts, 2),
+            Ok(board) => panic!("Expected no board, got {}", board.as_line_string()),
+            Err(e) => panic!("Expected too many attempts, got {}", e),
+        }
+    }
+
+    #[test]
+    fn matching_difficulty_is_found() {
+        let board = RandomLoader::from_seed(0)
+            .with_target_difficulty(Difficulty::Easy)
+            .into_game()
+            .unwrap();
+        let solution = Solver::default().solve(&board).unwrap_or_else(|_| panic!("Not rated"));
+        assert_eq!(solution.difficulty, Difficulty::Easy);
+    }
+
+    #[test]
+    fn deadline_stops_generation() {
+        let result = RandomLoader::from_seed(0)
+            .with_givens(MIN_STARTING_CELLS)
+            .with_deadline(Duration::from_millis(1))
+            .into_game();
+        assert!(matches!(result, Err(RandomCreatorError::Timeout)));
+    }
+
+    #[test]
+    fn progress_can_borrow_local_state() {
+        let mut reports = vec![];
+        RandomLoader::from_seed(0)
+            .with_progress(|progress| reports.push(progress))
+            .into_game()
+            .unwrap();
+
+        assert!(!reports.is_empty());
+        assert!(reports
+            .iter()
+            .all(|report| (0.0..=1.0).contains(&report.fraction)));
+        assert_eq!(reports.last().unwrap().phase, GenerationPhase::Removing);
+    }
+
+    fn starting_cells(board: &GameBoard) -> Vec<CellIndex> {
+        (0..9)
+            .flat_map(|row| (0..9).map(move |column| (column, row)))
+            .filter(|&index| matches!(board[index], CellValue::Preset(_)))
+            .collect()
+    }
+
+    #[test]
+    fn symmetric_boards_have_the_requested_starting_cells() {
+        for &givens in &[30, 31] {
+            let board = RandomLoader::from_seed(0)
+                .with_givens(givens)
+                .symmetric(true)
+                .into_game()
+                .unwrap();
+            let cells = starting_cells(&board);
+            assert_eq!(cells.len(), givens);
+            for &(column, row) in &cells {
+                assert!(cells.contains(&(8 - column, 8 - row)));
+            }
+        }
+    }
+
+    #[test]
+    fn stuck_boards_are_not_returned() {
+        let result = RandomLoader::from_seed(0)
+            .with_givens(MIN_STARTING_CELLS)
+            .with_max_attempts(2)
+            .into_game();
+        match result {
+            Err(RandomCreatorError::TooManyAttempts(attempts)) => assert_eq!(attempts, 2),
+            Ok(board) => panic!("Expected no board, got {}", board.as_line_string()),
+            Err(e) => panic!("Expected too many attempts, got {}", e),
+        }
+    }
+}
@@ -1,8 +1,14 @@
 //! Global game settings
 
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
 /// Contains global game settings
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameSettings {
-    /// Automatically fill in all possible maybe values at beginning of game
+    /// Automatically fill in all possible maybe values at beginning of game, or when it's turned on
     pub auto_note: bool,
     /// Automatically fill in cells with notes where no other value can be placed
     pub auto_fill: bool,
@@ -10,6 +16,8 @@ pub struct GameSettings {
     pub auto_remove: bool,
     /// Show if error cells are present
     pub show_errors: bool,
+    /// Only allow maybes of values that aren't already in the same row, column, or house
+    pub legal_notes_only: bool,
 }
 
 impl GameSettings {
@@ -20,6 +28,39 @@ impl GameSettings {
             auto_fill: false,
             auto_remove: true,
             show_errors: true,
+            legal_notes_only: false,
         }
     }
+
+    /// Loads settings saved with [save](GameSettings::save). Settings missing from the file keep
+    /// their default values.
+    ///
+    /// # Error:
+    /// This function will result in an error if an [IO error] occurs, or if the file doesn't
+    /// contain settings
+    ///
+    /// [IO error]: std::io::Error
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Saves the settings to a file as JSON.
+    ///
+    /// # Error:
+    /// This function will result in an error if an [IO error] occurs
+    ///
+    /// [IO error]: std::io::Error
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -9,17 +9,21 @@ extern crate serde;
 
 use std::str::FromStr;
 
-use clap::{App, Arg};
+use clap::{App, Arg, ArgGroup};
 use glutin_window::{GlutinWindow, OpenGL};
 use opengl_graphics::{Filter, GlGraphics, GlyphCache, TextureSettings};
-use piston::{event_loop::EventLoop, Events, EventSettings, RenderEvent, WindowSettings};
+use piston::{
+    event_loop::EventLoop, Events, EventSettings, RenderEvent, ResizeEvent, WindowSettings,
+};
 
 pub use game_board::*;
 pub use game_board_controller::GameBoardController;
 pub use game_board_view::{GameBoardView, GameBoardViewSettings};
 pub use game_settings::GameSettings;
 
-use crate::game_creator::{ByteStringLoader, GameCreator, RandomLoader};
+use crate::advanced_solver::Solver;
+use crate::game_creator::{ByteStringLoader, GameCreator, JSONLoader, LineLoader, RandomLoader};
+use std::time::Duration;
 
 pub mod advanced_solver;
 mod game_board;
@@ -27,8 +31,13 @@ mod game_board_controller;
 mod game_board_view;
 pub mod game_creator;
 mod game_settings;
+pub mod svg;
+pub mod ui;
 pub mod validity;
 
+/// The file that game settings are saved in
+const SETTINGS_PATH: &str = "settings.json";
+
 fn main() {
     let board: GameBoard;
 
@@ -47,7 +56,38 @@ fn main() {
                 .long("rand")
                 .min_values(0)
                 .max_values(1)
-                .conflicts_with_all(&["byte_string"]),
+                .conflicts_with_all(&["byte_string", "line", "json"]),
+        )
+        .arg(
+            Arg::with_name("line")
+                .help("Uses a line of 81 digits to create a sudoku board, with 0 or . for empty cells")
+                .takes_value(true)
+                .short("l")
+                .long("line")
+                .conflicts_with("byte_string"),
+        )
+        .arg(
+            Arg::with_name("json")
+                .help("Loads a sudoku board from a json file")
+                .takes_value(true)
+                .short("j")
+                .long("json")
+                .conflicts_with_all(&["byte_string", "line"]),
+        )
+        .group(ArgGroup::with_name("input").args(&["byte_string", "line", "json"]))
+        .arg(
+            Arg::with_name("solve")
+                .help("Prints the solution of the board as a line instead of playing it")
+                .short("s")
+                .long("solve")
+                .requires("input"),
+        )
+        .arg(
+            Arg::with_name("rate")
+                .help("Prints the difficulty of the board instead of playing it")
+                .long("rate")
+                .requires("input")
+                .conflicts_with("solve"),
         )
         .arg(
             Arg::with_name("cells")
@@ -65,25 +105,29 @@ fn main() {
         board = loader
             .into_game()
             .expect("Could not create game from byte string");
+    } else if let Some(line) = app.value_of("line") {
+        let loader = LineLoader::from_string(line);
+        board = loader.into_game().expect("Could not create game from line");
+    } else if let Some(path) = app.value_of("json") {
+        let loader = JSONLoader::from_file(path).expect("Could not read json file");
+        board = loader.into_game().expect("Could not create game from json");
     } else if app.is_present("random") {
-        let starting = if let Some(starting) = app.value_of("cells") {
-            Some(usize::from_str(starting).expect("Starting cells count must be an integer"))
-        } else {
-            None
-        };
+        let starting = app.value_of("cells").map(|starting| {
+            usize::from_str(starting).expect("Starting cells count must be an integer")
+        });
         board = match app.value_of("random") {
             Some(v) => {
                 let num: u64 = v.parse().expect("Given seed is not an integer");
-                let mut loader = RandomLoader::from_seed(num);
+                let mut loader = RandomLoader::from_seed(num).verbose(true);
                 if let Some(starting) = starting {
-                    loader.num_starting_cells = starting;
+                    loader = loader.with_givens(starting);
                 }
                 loader.into_game().expect("Could not create a random game")
             }
             None => {
-                let mut loader = RandomLoader::new();
+                let mut loader = RandomLoader::new().verbose(true);
                 if let Some(starting) = starting {
-                    loader.num_starting_cells = starting;
+                    loader = loader.with_givens(starting);
                 }
                 loader.into_game().expect("Could not create a random game")
             }
@@ -92,42 +136,81 @@ fn main() {
         board = GameBoard::new();
     }
 
+    if app.is_present("rate") {
+        let solver = Solver::new(Duration::from_secs(2));
+        match solver.solve(&board) {
+            Ok(solution) => {
+                println!("Difficulty: {:?}", solution.difficulty);
+                println!("Points: {}", solution.points);
+            }
+            Err(_) => {
+                eprintln!("Could not rate the sudoku puzzle, as it can't be solved using known techniques");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if app.is_present("solve") {
+        let solver = Solver::new(Duration::from_secs(2));
+        let solution = match solver.solve(&board) {
+            Ok(solution) => solution.solved_board,
+            Err(_) => {
+                // the known techniques weren't enough, so brute force the rest
+                let mut solution = board;
+                if !solution.solve() {
+                    eprintln!("Could not solve the sudoku puzzle");
+                    std::process::exit(1);
+                }
+                solution
+            }
+        };
+        println!("{}", solution.as_line_string());
+        return;
+    }
+
     let opengl = OpenGL::V3_2;
-    let settings = WindowSettings::new("Sudoku", [512; 2])
+    let settings = WindowSettings::new("Sudoku", [512, 532])
         .graphics_api(opengl)
         .exit_on_esc(true);
     let mut window: GlutinWindow = settings.build().expect("Could not make window");
 
-    let mut events = Events::new(EventSettings::new().lazy(true));
+    // not lazy, so that the timer keeps being redrawn
+    let mut events = Events::new(EventSettings::new().lazy(false));
     let mut gl = GlGraphics::new(opengl);
 
     let texture_settings = TextureSettings::new().filter(Filter::Nearest);
-    let ref mut glyph_cache = GlyphCache::new("assets/FiraSans-Regular.ttf", (), texture_settings)
+    let glyph_cache = &mut GlyphCache::new("assets/FiraSans-Regular.ttf", (), texture_settings)
         .expect("Could not load font");
 
-    let game_settings = GameSettings::new();
+    // settings that haven't been saved yet use the defaults
+    let game_settings = GameSettings::load(SETTINGS_PATH).unwrap_or_default();
 
-    let mut controller = GameBoardController::new(board);
+    let mut controller = GameBoardController::new(board).with_settings(game_settings);
     let game_view_settings = GameBoardViewSettings::new();
-    let board_view = GameBoardView::new(game_view_settings);
+    let mut board_view = GameBoardView::new(game_view_settings);
 
 
     while let Some(event) = events.next(&mut window) {
-        controller.event(
-            board_view.settings.position,
-            board_view.settings.size,
-            &event,
-        );
+        if let Some(args) = event.resize_args() {
+            board_view.settings.resize(args.window_size);
+            controller.layout_buttons(board_view.settings.position, board_view.settings.size);
+        }
+        controller.event(board_view.settings.position, board_view.settings.size, &event);
         if let Some(args) = event.render_args() {
             gl.draw(args.viewport(), |c, g| {
                 use graphics::clear;
 
-                clear([1.0; 4], g);
+                clear(board_view.settings.window_background_color, g);
 
-                board_view.draw(&game_settings, &controller, glyph_cache, &c, g);
+                board_view.draw(&controller, glyph_cache, &c, g);
             })
         }
     }
 
+    if let Err(e) = controller.game_settings.save(SETTINGS_PATH) {
+        eprintln!("Could not save settings: {}", e);
+    }
+
     println!("{}", settings.get_exit_on_esc());
 }
@@ -0,0 +1,175 @@
+//! Export a game board as an SVG image
+//!
+//! The image is laid out like the board is drawn in the game: thin lines between cells, thick
+//! lines around houses, and notes placed in a small grid inside of their cell, with one spot for
+//! each value.
+
+use crate::game_board::value_char;
+use crate::{CellValue, GameBoard};
+use std::fmt::Write;
+
+/// Styling for an exported SVG
+pub struct SvgOptions {
+    /// The width and height of each cell, in pixels
+    pub cell_size: f64,
+    /// The font family used for values and notes
+    pub font_family: String,
+    /// The color of preset values
+    pub preset_color: String,
+    /// The color of values entered by the player
+    pub value_color: String,
+    /// The color of notes
+    pub note_color: String,
+    /// Draw the values noted as maybes in cells without a value
+    pub show_notes: bool,
+}
+
+impl SvgOptions {
+    /// Creates the default options, which has 50 pixel cells and no notes
+    pub fn new() -> Self {
+        Self {
+            cell_size: 50.0,
+            font_family: "sans-serif".to_string(),
+            preset_color: "#000000".to_string(),
+            value_color: "#1e50a0".to_string(),
+            note_color: "#808080".to_string(),
+            show_notes: false,
+        }
+    }
+
+    /// Sets the width and height of each cell
+    pub fn with_cell_size(mut self, cell_size: f64) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /// Sets the font family used for values and notes
+    pub fn with_font_family<S: AsRef<str>>(mut self, font_family: S) -> Self {
+        self.font_family = font_family.as_ref().to_string();
+        self
+    }
+
+    /// Sets whether maybe notes are drawn
+    pub fn with_notes(mut self, show_notes: bool) -> Self {
+        self.show_notes = show_notes;
+        self
+    }
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameBoard {
+    /// Creates a standalone SVG image of the board
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let size = self.size();
+        let cell_size = options.cell_size;
+        let board_size = cell_size * size as f64;
+        let thin = cell_size / 50.0;
+        let thick = thin * 3.0;
+        // leave room for half of the outer border on every side
+        let margin = thick / 2.0;
+
+        let mut svg = String::new();
+        // writing to a string can't fail
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="{1} {1} {0} {0}">"#,
+            board_size + thick,
+            -margin
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect x="0" y="0" width="{0}" height="{0}" fill="white"/>"#,
+            board_size
+        );
+
+        // lines between cells, with house edges drawn thicker
+        for i in 0..=size {
+            let offset = i as f64 * cell_size;
+            let vertical = if i % self.house_width() == 0 { thick } else { thin };
+            let horizontal = if i % self.house_height() == 0 { thick } else { thin };
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{0}" y1="0" x2="{0}" y2="{1}" stroke="black" stroke-width="{2}" stroke-linecap="square"/>"#,
+                offset, board_size, vertical
+            );
+            let _ = writeln!(
+                svg,
+                r#"<line x1="0" y1="{0}" x2="{1}" y2="{0}" stroke="black" stroke-width="{2}" stroke-linecap="square"/>"#,
+                offset, board_size, horizontal
+            );
+        }
+
+        let note_width = cell_size / self.house_width() as f64;
+        let note_height = cell_size / self.house_height() as f64;
+        for row in 0..size {
+            for column in 0..size {
+                let x = column as f64 * cell_size;
+                let y = row as f64 * cell_size;
+                match self.cell_value((column, row)) {
+                    &CellValue::Preset(val) => {
+                        write_text(
+                            &mut svg,
+                            options,
+                            [x + cell_size / 2.0, y + cell_size / 2.0],
+                            cell_size * 0.6,
+                            &options.preset_color,
+                            value_char(val),
+                        );
+                    }
+                    &CellValue::Value(val) => {
+                        write_text(
+                            &mut svg,
+                            options,
+                            [x + cell_size / 2.0, y + cell_size / 2.0],
+                            cell_size * 0.6,
+                            &options.value_color,
+                            value_char(val),
+                        );
+                    }
+                    notes @ CellValue::Notes { .. } if options.show_notes => {
+                        for val in notes.maybe_values().into_iter().flatten() {
+                            let i = (val - 1) as usize % self.house_width();
+                            let j = (val - 1) as usize / self.house_width();
+                            write_text(
+                                &mut svg,
+                                options,
+                                [
+                                    x + (i as f64 + 0.5) * note_width,
+                                    y + (j as f64 + 0.5) * note_height,
+                                ],
+                                note_height * 0.7,
+                                &options.note_color,
+                                value_char(val),
+                            );
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// Writes a character centered on a position
+fn write_text(
+    svg: &mut String,
+    options: &SvgOptions,
+    center: [f64; 2],
+    font_size: f64,
+    color: &str,
+    ch: char,
+) {
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-family="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+        center[0], center[1], options.font_family, font_size, color, ch
+    );
+}
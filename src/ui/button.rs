@@ -0,0 +1,69 @@
+//! A clickable button
+
+use crate::{GameBoardController, GameBoardViewSettings};
+use graphics::{character::CharacterCache, Context, Graphics, Rectangle, Text, Transformed};
+
+/// A rectangular area of the screen that does something to the game when it's clicked
+pub struct Button {
+    /// The text shown on the button
+    pub label: String,
+    /// The position and size of the button, as `[x, y, width, height]`
+    pub rect: [f64; 4],
+    /// Whether the mouse is over the button
+    pub hovered: bool,
+    /// Called with the controller when the button is clicked
+    pub on_click: Box<dyn FnMut(&mut GameBoardController)>,
+}
+
+impl Button {
+    /// Creates a new button
+    pub fn new<S, F>(label: S, rect: [f64; 4], on_click: F) -> Self
+    where
+        S: AsRef<str>,
+        F: FnMut(&mut GameBoardController) + 'static,
+    {
+        Button {
+            label: label.as_ref().to_string(),
+            rect,
+            hovered: false,
+            on_click: Box::new(on_click),
+        }
+    }
+
+    /// Checks whether a position is within the button
+    pub fn contains(&self, pos: [f64; 2]) -> bool {
+        let [x, y, width, height] = self.rect;
+        pos[0] >= x && pos[0] < x + width && pos[1] >= y && pos[1] < y + height
+    }
+
+    /// Draw the button
+    pub fn draw<G: Graphics, C>(
+        &self,
+        settings: &GameBoardViewSettings,
+        glyphs: &mut C,
+        c: &Context,
+        g: &mut G,
+    ) where
+        C: CharacterCache<Texture = G::Texture>,
+    {
+        let color = if self.hovered {
+            settings.button_hover_color
+        } else {
+            settings.button_color
+        };
+        Rectangle::new(color).draw(self.rect, &c.draw_state, c.transform, g);
+        Rectangle::new_border(settings.board_edge_color, settings.cell_edge_radius).draw(
+            self.rect,
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+
+        let [x, y, _, height] = self.rect;
+        let transform = c.transform.trans(x + 10.0, y + height / 2.0 + 6.0);
+        Text::new_color(settings.button_text_color, 16)
+            .draw(&self.label, glyphs, &c.draw_state, transform, g)
+            .map_err(|_| "Couldn't write text to screen")
+            .unwrap();
+    }
+}
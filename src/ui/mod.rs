@@ -0,0 +1,4 @@
+//! User interface elements that are drawn alongside the game board
+
+mod button;
+pub use button::Button;
@@ -1,58 +1,85 @@
 //! Validity trait for Sudoku components
 
 use crate::game_board::CellIndex;
-use crate::{CellValue, GameBoard, SIZE};
+use crate::{CellValue, GameBoard, GameBoardVariant, MAX_SIZE};
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 
 /// A helper trait that is used to determine whether a component of a cell is invalid
 pub trait SudokuCorrectness {
     /// Checks whether the component is valid, by not having repeating cells
     fn is_valid(&self) -> bool {
-        self.invalid_cells().is_empty()
+        let mut found = [false; MAX_SIZE];
+        let mut valid = true;
+        self.visit_cells(&mut |_, cell| {
+            if let Some(val) = cell.as_value() {
+                valid &= !found[(val - 1) as usize];
+                found[(val - 1) as usize] = true;
+            }
+        });
+        valid
     }
     /// Cell indices for invalid cells
     fn invalid_cells(&self) -> Vec<CellIndex> {
-        let mut found_array: [Result<Option<CellIndex>, ()>; SIZE] = [Ok(None); SIZE];
+        let mut found_array: [Result<Option<CellIndex>, ()>; MAX_SIZE] = [Ok(None); MAX_SIZE];
         let mut invalid = vec![];
 
-        for (index, val) in self.indices_and_values() {
-            match found_array[(val - 1) as usize] {
-                Ok(None) => {
-                    found_array[(val - 1) as usize] = Ok(Some(index));
-                }
-                Ok(Some(old)) => {
-                    invalid.push(old);
-                    invalid.push(index);
-                    found_array[(val - 1) as usize] = Err(());
+        self.visit_cells(&mut |index, cell| {
+            if let Some(val) = cell.as_value() {
+                match found_array[(val - 1) as usize] {
+                    Ok(None) => {
+                        found_array[(val - 1) as usize] = Ok(Some(index));
+                    }
+                    Ok(Some(old)) => {
+                        invalid.push(old);
+                        invalid.push(index);
+                        found_array[(val - 1) as usize] = Err(());
+                    }
+                    Err(_) => invalid.push(index),
                 }
-                Err(_) => invalid.push(index),
             }
-        }
+        });
 
         invalid
     }
 
     /// Whether all cells in the component are filled in and the component is valid
     fn is_complete(&self) -> bool {
-        let mut found = [0; SIZE];
-        for (_, val) in self.indices_and_values() {
-            found[(val - 1) as usize] += 1;
-        }
+        let mut found = [0; MAX_SIZE];
+        let mut count = 0;
+        self.visit_cells(&mut |_, cell| {
+            if let Some(val) = cell.as_value() {
+                found[(val - 1) as usize] += 1;
+            }
+            count += 1;
+        });
 
-        found.iter().all(|&v| v == 1)
+        found[..count].iter().all(|&v| v == 1)
     }
 
     /// Gets the index and value for each FILLED cell
     fn indices_and_values(&self) -> Vec<(CellIndex, u8)> {
-        self.indices_and_cells()
-            .into_iter()
-            .filter_map(|(index, cell)| cell.as_value().map(|value| (index, value)))
-            .collect()
+        let mut ret = vec![];
+        self.visit_cells(&mut |index, cell| {
+            if let Some(value) = cell.as_value() {
+                ret.push((index, value));
+            }
+        });
+        ret
     }
 
     /// Gets the index and value for each cell
-    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)>;
+    fn indices_and_cells(&self) -> Vec<(CellIndex, &CellValue)> {
+        let mut ret = vec![];
+        self.visit_cells(&mut |index, cell| ret.push((index, cell)));
+        ret
+    }
+
+    /// Calls the visitor with the index and value of each cell, in the same order as
+    /// [indices_and_cells](SudokuCorrectness::indices_and_cells) but without collecting them
+    fn visit_cells<'a>(&'a self, visitor: &mut dyn FnMut(CellIndex, &'a CellValue));
 }
 
 /// Allows for iterating through the indices and values that are mutable
@@ -73,17 +100,60 @@ pub struct SolutionsTree {
     head: Node,
 }
 
-impl SolutionsTree {
-    /// Creates a tree of solutions for the board
-    pub fn solve(board: &GameBoard) -> Option<Self> {
-        let ref mut counter = 0usize;
-        let maybe_ret = Node::solve(board, counter).map(|head| Self { head });
-        if *counter >= MAX_SOLUTION_SIZE {
-            None
+/// The result of trying to find every solution of a board
+pub enum SolveOutcome {
+    /// The board has no solutions
+    Unsolvable,
+    /// Every solution of the board
+    Tree(SolutionsTree),
+    /// The board has too many solutions for the tree to hold [MAX_SOLUTION_SIZE] nodes
+    TooMany,
+    /// The solver took longer than [SOLVER_TIMEOUT_TIME]
+    TimedOut,
+}
+
+impl SolveOutcome {
+    /// The outcome of a search, from why it stopped early if it did
+    fn new(head: Option<Node>, limits: &SearchLimits) -> Self {
+        if limits.too_many.load(Ordering::Relaxed) {
+            SolveOutcome::TooMany
+        } else if limits.timed_out.load(Ordering::Relaxed) {
+            SolveOutcome::TimedOut
         } else {
-            maybe_ret
+            match head {
+                Some(head) => SolveOutcome::Tree(SolutionsTree { head }),
+                None => SolveOutcome::Unsolvable,
+            }
+        }
+    }
+
+    /// The tree of solutions, if every solution was found
+    pub fn tree(self) -> Option<SolutionsTree> {
+        match self {
+            SolveOutcome::Tree(tree) => Some(tree),
+            _ => None,
         }
     }
+}
+
+impl SolutionsTree {
+    /// Creates a tree of solutions for the board
+    pub fn solve(board: &GameBoard) -> SolveOutcome {
+        let limits = SearchLimits::new(MAX_SOLUTION_SIZE, SOLVER_TIMEOUT_TIME);
+        let head = Node::solve(board, &limits);
+        SolveOutcome::new(head, &limits)
+    }
+
+    /// Creates a tree of solutions for the board, solving for each value of the first cell to be
+    /// filled on a separate thread. The threads share one count of the tree's nodes, so the tree
+    /// stops growing at the same size as with [solve].
+    ///
+    /// [solve]: SolutionsTree::solve
+    pub fn solve_parallel(board: &GameBoard) -> SolveOutcome {
+        let limits = SearchLimits::new(MAX_SOLUTION_SIZE, SOLVER_TIMEOUT_TIME);
+        let head = Node::solve_parallel(board, &limits);
+        SolveOutcome::new(head, &limits)
+    }
 
     /// Creates a tree and never stops
     pub fn force_solve(board: &GameBoard) -> Option<Self> {
@@ -106,6 +176,224 @@ impl SolutionsTree {
     }
 }
 
+/// Counts the solutions of a board, stopping once `limit` solutions have been found. Boards with a
+/// value used twice in the same unit have no solutions
+pub fn count_solutions(board: &GameBoard, limit: usize) -> usize {
+    count_solutions_within(board, limit, Duration::MAX).expect("Counting never times out")
+}
+
+/// Counts the solutions of a board like [count_solutions], but gives up and returns `None` if
+/// counting takes longer than `timeout`
+pub fn count_solutions_within(board: &GameBoard, limit: usize, timeout: Duration) -> Option<usize> {
+    let mut used = match UsedValues::new(board) {
+        Some(used) => used,
+        None => return Some(0),
+    };
+    let mut board = board.clone();
+    let mut count = 0;
+    let limits = SearchLimits::new(usize::MAX, timeout);
+    used.count_solutions(&mut board, limit, &mut count, &limits);
+    if limits.stopped() {
+        None
+    } else {
+        Some(count)
+    }
+}
+
+/// How far a search for solutions can go, and why it stopped early if it did. Shared between the
+/// threads of a parallel search
+struct SearchLimits {
+    /// The number of nodes in the tree so far
+    nodes: AtomicUsize,
+    max_nodes: usize,
+    start: Instant,
+    timeout: Duration,
+    /// Set when part of the search was skipped because the tree had too many nodes
+    too_many: AtomicBool,
+    /// Set when part of the search was skipped because it took too long
+    timed_out: AtomicBool,
+}
+
+impl SearchLimits {
+    /// Limits for a search starting now
+    fn new(max_nodes: usize, timeout: Duration) -> Self {
+        SearchLimits {
+            nodes: AtomicUsize::new(0),
+            max_nodes,
+            start: Instant::now(),
+            timeout,
+            too_many: AtomicBool::new(false),
+            timed_out: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether the search has to stop before going any further, remembering why it did
+    fn should_stop(&self) -> bool {
+        if self.nodes.load(Ordering::Relaxed) >= self.max_nodes {
+            self.too_many.store(true, Ordering::Relaxed);
+            true
+        } else if self.start.elapsed() >= self.timeout {
+            self.timed_out.store(true, Ordering::Relaxed);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether any part of the search has been skipped
+    fn stopped(&self) -> bool {
+        self.too_many.load(Ordering::Relaxed) || self.timed_out.load(Ordering::Relaxed)
+    }
+
+    fn add_node(&self) {
+        self.nodes.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Bitmasks of the values used in each row, column, house, and diagonal of a board, where bit
+/// `val - 1` is set if `val` is used. Placing or removing a value only updates the masks it's in,
+/// instead of checking the whole board again
+#[derive(Clone)]
+struct UsedValues {
+    rows: [u32; MAX_SIZE],
+    columns: [u32; MAX_SIZE],
+    houses: [u32; MAX_SIZE],
+    diagonals: [u32; 2],
+    size: usize,
+    house_width: usize,
+    house_height: usize,
+    diagonal: bool,
+}
+
+impl UsedValues {
+    /// Creates the masks for a board. Returns `None` if a value is used twice in the same unit
+    fn new(board: &GameBoard) -> Option<Self> {
+        let mut used = UsedValues {
+            rows: [0; MAX_SIZE],
+            columns: [0; MAX_SIZE],
+            houses: [0; MAX_SIZE],
+            diagonals: [0; 2],
+            size: board.size(),
+            house_width: board.house_width(),
+            house_height: board.house_height(),
+            diagonal: board.variant() == GameBoardVariant::Diagonal,
+        };
+
+        for (cell_index, val) in board.indices_and_values() {
+            if !used.can_place(cell_index, val) {
+                return None;
+            }
+            used.place(cell_index, val);
+        }
+        Some(used)
+    }
+
+    fn house(&self, (column, row): CellIndex) -> usize {
+        row / self.house_height * self.house_height + column / self.house_width
+    }
+
+    /// The diagonals a cell is on, if the board has diagonal rules
+    fn diagonals(&self, (column, row): CellIndex) -> [bool; 2] {
+        [
+            self.diagonal && column == row,
+            self.diagonal && column + row == self.size - 1,
+        ]
+    }
+
+    /// The values that are used by a unit the cell is in
+    fn mask(&self, cell_index: CellIndex) -> u32 {
+        let (column, row) = cell_index;
+        let mut mask = self.rows[row] | self.columns[column] | self.houses[self.house(cell_index)];
+        for (n, &on_diagonal) in self.diagonals(cell_index).iter().enumerate() {
+            if on_diagonal {
+                mask |= self.diagonals[n];
+            }
+        }
+        mask
+    }
+
+    fn can_place(&self, cell_index: CellIndex, val: u8) -> bool {
+        self.mask(cell_index) & (1 << (val - 1)) == 0
+    }
+
+    fn place(&mut self, cell_index: CellIndex, val: u8) {
+        self.toggle(cell_index, val);
+    }
+
+    fn remove(&mut self, cell_index: CellIndex, val: u8) {
+        self.toggle(cell_index, val);
+    }
+
+    fn toggle(&mut self, cell_index: CellIndex, val: u8) {
+        let bit = 1 << (val - 1);
+        let (column, row) = cell_index;
+        let house = self.house(cell_index);
+        self.rows[row] ^= bit;
+        self.columns[column] ^= bit;
+        self.houses[house] ^= bit;
+        for (n, &on_diagonal) in self.diagonals(cell_index).iter().enumerate() {
+            if on_diagonal {
+                self.diagonals[n] ^= bit;
+            }
+        }
+    }
+
+    /// Finds the unset cell with the fewest values that can be placed in it, and those values as
+    /// a mask
+    fn most_constrained_cell(&self, board: &GameBoard) -> Option<(CellIndex, u32)> {
+        let all = (1u32 << self.size) - 1;
+        let mut best: Option<(CellIndex, u32)> = None;
+        for row in 0..self.size {
+            for column in 0..self.size {
+                let cell_index = (column, row);
+                if board[cell_index].as_value().is_some() {
+                    continue;
+                }
+                let candidates = all & !self.mask(cell_index);
+                if best.is_none_or(|(_, best)| candidates.count_ones() < best.count_ones()) {
+                    best = Some((cell_index, candidates));
+                    if candidates.count_ones() <= 1 {
+                        return best;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Adds the solutions of the board to `count`, placing and removing values in place, until
+    /// `count` reaches `limit` or the search has to stop
+    fn count_solutions(
+        &mut self,
+        board: &mut GameBoard,
+        limit: usize,
+        count: &mut usize,
+        limits: &SearchLimits,
+    ) {
+        if limits.should_stop() {
+            return;
+        }
+
+        match self.most_constrained_cell(board) {
+            Some((cell_index, candidates)) => {
+                let old = board[cell_index];
+                for val in (1..=self.size as u8).filter(|val| candidates & (1 << (val - 1)) != 0) {
+                    board[cell_index] = CellValue::Value(val);
+                    self.place(cell_index, val);
+                    self.count_solutions(board, limit, count, limits);
+                    self.remove(cell_index, val);
+                    board[cell_index] = old;
+
+                    if *count >= limit || limits.stopped() {
+                        return;
+                    }
+                }
+            }
+            None => *count += 1,
+        }
+    }
+}
+
 struct Node {
     board: GameBoard,
     node_type: NodeType,
@@ -125,40 +413,35 @@ impl Node {
         Node { board, node_type }
     }
 
-    fn solve_helper(board: &GameBoard, counter: &mut usize, instant: Instant, max_solution_size: usize, timeout_time: Duration) -> Option<Self> {
-        if *counter >= max_solution_size || instant.elapsed() >= timeout_time {
+    fn solve_helper(
+        board: &mut GameBoard,
+        used: &mut UsedValues,
+        limits: &SearchLimits,
+    ) -> Option<Self> {
+        if limits.should_stop() {
             return None;
         }
 
-        let mut cell: Option<CellIndex> = None;
-        'OUTER: for j in 0..9 {
-            for i in 0..9 {
-                let index: CellIndex = (j, i);
-                let value = board.cell_value(index);
-                if value.as_value().is_none() {
-                    cell = Some(index);
-                    break 'OUTER;
-                }
-            }
-        }
-
-        match cell {
-            Some(cell_index) => {
-                // Iterate through all values 0 through 9
-                // Check if that value can be place. If so, create a new board with that filled and solve
-                // from there, add result to this present
+        match used.most_constrained_cell(board) {
+            Some((cell_index, candidates)) => {
+                // Iterate through the values that can be placed in the cell with the fewest of them.
+                // Place each value in the board, solve from there, and add the result to this
+                // node before taking the value back out
 
                 let mut map = HashMap::new();
+                let old = board[cell_index];
+
+                for val in (1..=board.size() as u8).filter(|val| candidates & (1 << (val - 1)) != 0) {
+                    board[cell_index] = CellValue::Value(val);
+                    used.place(cell_index, val);
+                    let child = Node::solve_helper(board, used, limits);
+                    used.remove(cell_index, val);
+                    board[cell_index] = old;
 
-                for val in 1..=9 {
-                    let mut next = board.clone();
-                    next[cell_index] = CellValue::Value(val);
-                    if next.is_valid() {
-                        if let Some(child) = Node::solve_helper(&next, counter, instant, max_solution_size, timeout_time) {
-                            map.insert(val, child);
-                        }
+                    if let Some(child) = child {
+                        map.insert(val, child);
                     }
-                    if *counter >= max_solution_size || instant.elapsed() >= timeout_time {
+                    if limits.stopped() {
                         break;
                     }
                 }
@@ -170,38 +453,77 @@ impl Node {
                         next_cell: cell_index,
                         children: map,
                     };
-                    *counter += 1;
+                    limits.add_node();
                     Some(Node::new(board.clone(), inner))
                 }
             }
             None => {
-                if board.is_valid() && board.is_complete() {
-                    *counter += 1;
-                    Some(Node::new(board.clone(), NodeType::Leaf))
-                } else {
-                    None
-                }
+                // every value was checked against the used values when it was placed
+                limits.add_node();
+                Some(Node::new(board.clone(), NodeType::Leaf))
             }
         }
     }
 
-    fn solve(board: &GameBoard, counter: &mut usize) -> Option<Self> {
-        Self::solve_helper(board, counter, Instant::now(), MAX_SOLUTION_SIZE, SOLVER_TIMEOUT_TIME)
+    fn solve(board: &GameBoard, limits: &SearchLimits) -> Option<Self> {
+        let mut used = UsedValues::new(board)?;
+        let mut board = board.clone();
+        Self::solve_helper(&mut board, &mut used, limits)
     }
 
     fn force_solve(board: &GameBoard) -> Option<Self> {
-        let mut counter = 0;
-        Self::solve_helper(board, &mut counter, Instant::now(), usize::MAX, Duration::MAX)
+        Self::solve(board, &SearchLimits::new(usize::MAX, Duration::MAX))
+    }
+
+    fn solve_parallel(board: &GameBoard, limits: &SearchLimits) -> Option<Self> {
+        let used = UsedValues::new(board)?;
+        let (cell_index, candidates) = match used.most_constrained_cell(board) {
+            Some(found) => found,
+            None => return Self::solve(board, limits),
+        };
+
+        // Every value shares the limits, so all of them stop once the tree is too big together
+        let values: Vec<u8> = (1..=board.size() as u8)
+            .filter(|val| candidates & (1 << (val - 1)) != 0)
+            .collect();
+        let children: Vec<(u8, Option<Node>)> = values
+            .into_par_iter()
+            .map(|val| {
+                let mut board = board.clone();
+                let mut used = used.clone();
+                board[cell_index] = CellValue::Value(val);
+                used.place(cell_index, val);
+                (val, Node::solve_helper(&mut board, &mut used, limits))
+            })
+            .collect();
+
+        let mut map = HashMap::new();
+        for (val, child) in children {
+            if let Some(child) = child {
+                map.insert(val, child);
+            }
+        }
+
+        if map.is_empty() {
+            None
+        } else {
+            let inner = NodeType::Branch {
+                next_cell: cell_index,
+                children: map,
+            };
+            limits.add_node();
+            Some(Node::new(board.clone(), inner))
+        }
     }
 
     fn try_solve(board: &GameBoard, cell_index: CellIndex, old_val: u8) -> Option<Self> {
-        // Iterate through all values 0 through 9
+        // Iterate through all values 1 through the size of the board
         // Check if that value can be place. If so, create a new board with that filled and solve
         // from there, add result to this present
 
         let mut map = HashMap::new();
 
-        for val in 1..=9 {
+        for val in 1..=board.size() as u8 {
             if old_val == val {
                 continue;
             }
@@ -245,12 +567,8 @@ impl Node {
                 next_cell: _,
                 children,
             } => {
-                for i in 1..=9 {
-                    if let Some(next) = children.get(&i) {
-                        return next.first_solution();
-                    }
-                }
-                unreachable!()
+                let first = children.keys().min().unwrap();
+                children[first].first_solution()
             }
         }
     }
@@ -260,30 +578,101 @@ impl Node {
 pub fn can_be_completed(board: &GameBoard) -> bool {
     let mut board = board.clone();
 
-    if !board.is_valid() {
+    board.clear_notes();
+    if board.auto_note().is_err() {
         return false;
     }
-    board.clear_notes();
-    board.auto_note();
-
-    for cell in &board {
-        match cell {
-            CellValue::Notes { .. } => {
-                if let Some(maybe) = cell.maybe_values() {
-                    if maybe.is_empty() {
-                        return false;
-                    }
-                }
-            }
-            CellValue::Empty => return false,
-            _ => {}
+
+    // finding a single solution is enough, and doesn't depend on a timeout
+    board.solve()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_creator::{GameCreator, LineLoader};
+
+    /// A puzzle with 17 givens and one solution
+    const UNIQUE: &str =
+        "000000010400000000020000000000050407008000300001090000300400200050100000000806000";
+    /// A solved board with the 1s and 2s of rows 1 and 4 removed from the first two columns, which
+    /// can be put back either way around
+    const TWO_SOLUTIONS: &str =
+        "003456789456789123789123456004365897365897214897214365531642978642978531978531642";
+
+    fn board(line: &str) -> GameBoard {
+        LineLoader::from_string(line).into_game().unwrap()
+    }
+
+    /// The number of solutions in the tree, or why there's no tree
+    fn summary(outcome: SolveOutcome) -> Result<usize, &'static str> {
+        match outcome {
+            SolveOutcome::Unsolvable => Err("unsolvable"),
+            SolveOutcome::Tree(tree) => Ok(tree.num_solutions()),
+            SolveOutcome::TooMany => Err("too many"),
+            SolveOutcome::TimedOut => Err("timed out"),
         }
     }
 
-    let mut counter = 0;
-    if let Some(_) = Node::solve(&mut board, &mut counter) {
-        counter > 0
-    } else {
-        false
+    #[test]
+    fn parallel_matches_sequential_on_unique_board() {
+        let board = board(UNIQUE);
+        assert_eq!(summary(SolutionsTree::solve(&board)), Ok(1));
+        assert_eq!(summary(SolutionsTree::solve_parallel(&board)), Ok(1));
+    }
+
+    #[test]
+    fn parallel_matches_sequential_on_board_with_two_solutions() {
+        let board = board(TWO_SOLUTIONS);
+        assert_eq!(summary(SolutionsTree::solve(&board)), Ok(2));
+        assert_eq!(summary(SolutionsTree::solve_parallel(&board)), Ok(2));
+    }
+
+    #[test]
+    fn parallel_stops_at_the_same_size_as_sequential() {
+        let board = GameBoard::new();
+        assert_eq!(summary(SolutionsTree::solve(&board)), Err("too many"));
+        assert_eq!(summary(SolutionsTree::solve_parallel(&board)), Err("too many"));
+    }
+
+    #[test]
+    fn search_finished_before_deadline_keeps_its_tree() {
+        let board = board(TWO_SOLUTIONS);
+        let mut limits = SearchLimits::new(MAX_SOLUTION_SIZE, Duration::from_secs(10));
+        let head = Node::solve(&board, &limits);
+        // the deadline passing after the search is done doesn't make it time out
+        limits.timeout = Duration::ZERO;
+        assert_eq!(summary(SolveOutcome::new(head, &limits)), Ok(2));
+    }
+
+    #[test]
+    fn search_stopped_by_deadline_times_out() {
+        let board = board(UNIQUE);
+        let limits = SearchLimits::new(MAX_SOLUTION_SIZE, Duration::ZERO);
+        let head = Node::solve(&board, &limits);
+        assert_eq!(summary(SolveOutcome::new(head, &limits)), Err("timed out"));
+    }
+
+    #[test]
+    fn search_that_fills_the_tree_exactly_is_not_too_many() {
+        let board = board(TWO_SOLUTIONS);
+        let nodes = {
+            let limits = SearchLimits::new(usize::MAX, Duration::MAX);
+            Node::solve(&board, &limits);
+            limits.nodes.into_inner()
+        };
+        let limits = SearchLimits::new(nodes, Duration::MAX);
+        let head = Node::solve(&board, &limits);
+        assert_eq!(summary(SolveOutcome::new(head, &limits)), Ok(2));
+    }
+
+    #[test]
+    fn counting_gives_up_at_the_timeout() {
+        let count = count_solutions_within(&GameBoard::new(), usize::MAX, Duration::from_millis(20));
+        assert_eq!(count, None);
+        assert_eq!(
+            count_solutions_within(&board(TWO_SOLUTIONS), usize::MAX, Duration::from_secs(60)),
+            Some(2)
+        );
     }
 }